@@ -0,0 +1,3 @@
+# Saudi Arabic translations for Bevy mail.
+#
+# Saudi Arabic shows Hijri dates, but otherwise uses the Arabic messages in `ar.ftl`.
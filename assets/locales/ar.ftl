@@ -0,0 +1,50 @@
+# Arabic translations for Bevy mail.
+
+bevymail_logo_text = بريد بَڤِي!
+greeting_morning = صباح الخير { $user }
+greeting_afternoon = مساء الخير { $user }
+greeting_evening = مساء الخير { $user }
+greeting_morning_anonymous = صباح الخير
+greeting_afternoon_anonymous = مساء الخير
+greeting_evening_anonymous = مساء الخير
+
+## Folders
+
+folders = مجلدات
+inbox = وارد
+sent = صادر
+deleted = محذوف
+unread_messages = { $count ->
+        [zero] لا توجد رسائل غير مقروءة
+        [one] رسالة واحدة غير مقروءة
+        [two] رسالتان غير مقروءتين
+        [few] { $count } رسائل غير مقروءة
+        [many] { $count } رسالة غير مقروءة
+       *[other] { $count } رسالة غير مقروءة
+    }
+
+## Relative times
+
+relative_just_now = الآن
+relative_minutes_ago = { $count ->
+        [one] منذ دقيقة
+        [two] منذ دقيقتين
+        [few] منذ { $count } دقائق
+        [many] منذ { $count } دقيقة
+       *[other] منذ { $count } دقيقة
+    }
+relative_hours_ago = { $count ->
+        [one] منذ ساعة
+        [two] منذ ساعتين
+        [few] منذ { $count } ساعات
+        [many] منذ { $count } ساعة
+       *[other] منذ { $count } ساعة
+    }
+relative_yesterday = أمس
+relative_days_ago = { $count ->
+        [one] منذ يوم
+        [two] منذ يومين
+        [few] منذ { $count } أيام
+        [many] منذ { $count } يومًا
+       *[other] منذ { $count } يوم
+    }
@@ -0,0 +1,38 @@
+# English translations for Bevy mail.
+
+bevymail_logo_text = Bevy mail!
+greeting_morning = Good morning { $user }
+greeting_afternoon = Good afternoon { $user }
+greeting_evening = Good evening { $user }
+greeting_morning_anonymous = Good morning
+greeting_afternoon_anonymous = Good afternoon
+greeting_evening_anonymous = Good evening
+
+## Folders
+
+folders = Folders
+inbox = Inbox
+sent = Sent
+deleted = Deleted
+unread_messages = { $count ->
+        [0] No unread messages
+        [one] { $count } unread message
+       *[other] { $count } unread messages
+    }
+
+## Relative times
+
+relative_just_now = Just now
+relative_minutes_ago = { $count ->
+        [one] { $count } minute ago
+       *[other] { $count } minutes ago
+    }
+relative_hours_ago = { $count ->
+        [one] { $count } hour ago
+       *[other] { $count } hours ago
+    }
+relative_yesterday = Yesterday
+relative_days_ago = { $count ->
+        [one] { $count } day ago
+       *[other] { $count } days ago
+    }
@@ -0,0 +1,461 @@
+//! Unicode Bidirectional Algorithm (UAX #9) for `Text` entities and their `TextSpan` children.
+//!
+//! The text shaper runs the bidi algorithm on its own, but it only sees the concatenated
+//! string: it picks the paragraph direction from the first strong character instead of from
+//! the UI, and it cannot tell where one of our spans ends. [`BidiText`] resolves the levels of
+//! each paragraph across all spans with the paragraph direction we choose, and writes them back
+//! into the spans as directional isolates. The shaper then reorders the runs of every wrapped
+//! line exactly as resolved here, while each span keeps its own style.
+
+use crate::{direction::ComputedLayoutDirection, CurrentLanguage, LocaleRegistry};
+use bevy::{prelude::*, utils::HashSet};
+use unicode_bidi::{BidiClass, BidiInfo, Level};
+
+const LEFT_TO_RIGHT_MARK: char = '\u{200E}';
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+const LEFT_TO_RIGHT_ISOLATE: char = '\u{2066}';
+const RIGHT_TO_LEFT_ISOLATE: char = '\u{2067}';
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+const ARABIC_LETTER_MARK: char = '\u{061C}';
+
+/// Resolves bidi levels for this `Text` and its direct `TextSpan` children.
+///
+/// Paragraphs take their direction from the entity's [`TextDirection`], and the text is
+/// justified to the start of its first paragraph. A `Text` has a single justification, so
+/// paragraphs that resolve to different directions are reported with a warning, and should be
+/// spawned as separate `Text`s instead. The marks and isolates inserted into the text are
+/// stripped again before every update, so they should not be used in the source text itself.
+#[derive(Component, Default)]
+pub struct BidiText;
+
+/// The base direction of the paragraphs of a [`BidiText`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TextDirection {
+    /// The direction of the text node, see [`LayoutDirection`](crate::direction::LayoutDirection).
+    #[default]
+    Inherit,
+    /// Each paragraph takes the direction of its first strong character, falling back to
+    /// left-to-right, as in UAX #9 rules P2 and P3.
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+/// Keeps the bidi levels of [`BidiText`] entities up to date with their text and the locale.
+pub fn bidi_text_system(
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    mut root_query: Query<(
+        Entity,
+        Ref<BidiText>,
+        &mut Text,
+        Option<&mut TextLayout>,
+        Option<Ref<TextDirection>>,
+        Option<Ref<ComputedLayoutDirection>>,
+        Option<Ref<Children>>,
+    )>,
+    mut span_query: Query<(&mut TextSpan, &Parent)>,
+) {
+    let locale_level = if locale_registry.direction(&current_language.0) == Direction::Rtl {
+        Level::rtl()
+    } else {
+        Level::ltr()
+    };
+
+    let changed_parents: HashSet<Entity> = span_query
+        .iter_mut()
+        .filter(|(span, _)| span.is_changed())
+        .map(|(_, parent)| parent.get())
+        .collect();
+
+    for (entity, bidi_text, mut text, text_layout, text_direction, layout_direction, children) in
+        &mut root_query
+    {
+        if !(current_language.is_changed()
+            || bidi_text.is_added()
+            || text.is_changed()
+            || text_direction.as_ref().is_some_and(Ref::is_changed)
+            || layout_direction.as_ref().is_some_and(Ref::is_changed)
+            || children.as_ref().is_some_and(Ref::is_changed)
+            || changed_parents.contains(&entity))
+        {
+            continue;
+        }
+
+        let span_entities: Vec<Entity> = children
+            .iter()
+            .flat_map(|children| children.iter().copied())
+            .filter(|child| span_query.contains(*child))
+            .collect();
+        let mut sources = vec![strip_bidi_controls(&text.0)];
+        for &entity in &span_entities {
+            let (span, _) = span_query.get(entity).unwrap();
+            sources.push(strip_bidi_controls(&span.0));
+        }
+
+        let inherited_level = match layout_direction.as_deref() {
+            Some(layout_direction) if layout_direction.is_rtl() => Level::rtl(),
+            Some(_) => Level::ltr(),
+            None => locale_level,
+        };
+        let base_level = match text_direction.as_deref().copied().unwrap_or_default() {
+            TextDirection::Inherit => Some(inherited_level),
+            TextDirection::Auto => None,
+            TextDirection::Ltr => Some(Level::ltr()),
+            TextDirection::Rtl => Some(Level::rtl()),
+        };
+        let (encoded, paragraph_levels) = encode_bidi_levels(&sources, base_level);
+
+        if let Some(mut text_layout) = text_layout {
+            let (justify, mixed) = paragraph_justify(&paragraph_levels, inherited_level);
+            if mixed {
+                warn!(
+                    "{entity} has paragraphs in both directions, which are all justified to the \
+                     start of the first one; spawn one `Text` per paragraph to justify each to \
+                     its own start"
+                );
+            }
+            if text_layout.justify != justify {
+                text_layout.justify = justify;
+            }
+        }
+
+        let mut encoded = encoded.into_iter();
+        let root_text = encoded.next().unwrap();
+        if text.0 != root_text {
+            text.0 = root_text;
+        }
+        for (entity, span_text) in span_entities.into_iter().zip(encoded) {
+            let (mut span, _) = span_query.get_mut(entity).unwrap();
+            if span.0 != span_text {
+                span.0 = span_text;
+            }
+        }
+    }
+}
+
+/// Returns the justification of the start of the first of `paragraph_levels`, or of
+/// `default_level` if there are none, and whether other paragraphs start on the other side.
+fn paragraph_justify(paragraph_levels: &[Level], default_level: Level) -> (JustifyText, bool) {
+    let first_level = paragraph_levels.first().copied().unwrap_or(default_level);
+    let mixed = paragraph_levels
+        .iter()
+        .any(|level| level.is_rtl() != first_level.is_rtl());
+    let justify = if first_level.is_rtl() {
+        JustifyText::Right
+    } else {
+        JustifyText::Left
+    };
+    (justify, mixed)
+}
+
+fn strip_bidi_controls(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            !matches!(
+                *c,
+                LEFT_TO_RIGHT_MARK
+                    | RIGHT_TO_LEFT_MARK
+                    | ARABIC_LETTER_MARK
+                    | LEFT_TO_RIGHT_ISOLATE
+                    | RIGHT_TO_LEFT_ISOLATE
+                    | POP_DIRECTIONAL_ISOLATE
+            )
+        })
+        .collect()
+}
+
+/// Resolves the bidi levels of the concatenated `spans` and returns the spans with the levels
+/// made explicit, along with the level of every paragraph.
+///
+/// Paragraphs get `base_level`, or the direction of their first strong character when it is
+/// `None`. Every paragraph starts with a mark that pins that direction, and every run above the
+/// paragraph level is wrapped in one isolate per level, so that a shaper running UAX #9 on the
+/// result resolves the same levels, whatever the span boundaries are. Runs at an even level are
+/// wrapped one level lower: an odd level raises left-to-right text and numbers to the next
+/// level on its own, while an even one would raise numbers by two. An isolate that follows
+/// Arabic letters starts with an Arabic letter mark, so its numbers are still read as Arabic
+/// numbers.
+pub fn encode_bidi_levels(
+    spans: &[String],
+    base_level: Option<Level>,
+) -> (Vec<String>, Vec<Level>) {
+    let text = spans.concat();
+    let bidi_info = BidiInfo::new(&text, base_level);
+
+    // Index of the span containing each byte of `text`.
+    let span_of_byte: Vec<usize> = spans
+        .iter()
+        .enumerate()
+        .flat_map(|(index, span)| std::iter::repeat_n(index, span.len()))
+        .collect();
+    let mut output = vec![String::new(); spans.len()];
+
+    for paragraph in &bidi_info.paragraphs {
+        let paragraph_level = paragraph.level.number();
+        let mut level = paragraph_level;
+        // Whether the last strong character was an Arabic letter, which makes the European
+        // numbers after it Arabic numbers (UAX #9 rule W2).
+        let mut after_arabic_letter = false;
+        let mut span = span_of_byte
+            .get(paragraph.range.start)
+            .copied()
+            .unwrap_or_default();
+
+        output[span].push(if paragraph.level.is_rtl() {
+            RIGHT_TO_LEFT_MARK
+        } else {
+            LEFT_TO_RIGHT_MARK
+        });
+
+        for (offset, c) in text[paragraph.range.clone()].char_indices() {
+            let index = paragraph.range.start + offset;
+            span = span_of_byte[index];
+            let target = match bidi_info.levels[index].number() {
+                _ if bidi_info.original_classes[index] == BidiClass::B => paragraph_level,
+                level if level > paragraph_level && level % 2 == 0 => level - 1,
+                level => level,
+            };
+
+            while level > target {
+                output[span].push(POP_DIRECTIONAL_ISOLATE);
+                level -= 1;
+            }
+            // Each isolate raises the level by exactly one, as its direction alternates.
+            while level < target {
+                level += 1;
+                if level % 2 == 1 {
+                    output[span].push(RIGHT_TO_LEFT_ISOLATE);
+                    if after_arabic_letter {
+                        output[span].push(ARABIC_LETTER_MARK);
+                    }
+                } else {
+                    output[span].push(LEFT_TO_RIGHT_ISOLATE);
+                }
+            }
+
+            output[span].push(c);
+            match bidi_info.original_classes[index] {
+                BidiClass::AL => after_arabic_letter = true,
+                BidiClass::L | BidiClass::R => after_arabic_letter = false,
+                _ => {}
+            }
+        }
+
+        while level > paragraph_level {
+            output[span].push(POP_DIRECTIONAL_ISOLATE);
+            level -= 1;
+        }
+    }
+
+    let paragraph_levels = bidi_info
+        .paragraphs
+        .iter()
+        .map(|paragraph| paragraph.level)
+        .collect();
+    (output, paragraph_levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::tests::locale_registry;
+
+    const MAIL_MESSAGE: &str = "Here is some bi-directional text:
+One Two Three, اربعة خمسة ستة، seven eight (nine), عشرة أحد عشر (إثنا عشر)، thirteen fourteen fifteen عربي.
+
+وهنا المزيد من النص الثنائي الإتجاه:
+واحد إثنان ثلاثة، four five six, سبعة ثمانية (تسعة)، ten eleven (twelve), ثلاثة عشر أربعة عشر خمسة عشر English.";
+
+    /// Returns the levels of the characters of `text` that are not bidi controls, and every
+    /// paragraph in visual order without its controls, as a shaper would resolve them.
+    fn resolve(text: &str, base_level: Option<Level>) -> (Vec<(char, Level)>, Vec<String>) {
+        let bidi_info = BidiInfo::new(text, base_level);
+        let levels = text
+            .char_indices()
+            .filter(|(_, c)| strip_bidi_controls(&c.to_string()).len() == c.len_utf8())
+            .map(|(index, c)| (c, bidi_info.levels[index]))
+            .collect();
+        let visual = bidi_info
+            .paragraphs
+            .iter()
+            .map(|paragraph| {
+                let (levels, runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+                let mut visual = String::new();
+                for run in runs {
+                    let run_text = strip_bidi_controls(&text[run.clone()]);
+                    if levels[run.start].is_rtl() {
+                        visual.extend(run_text.chars().rev());
+                    } else {
+                        visual.push_str(&run_text);
+                    }
+                }
+                visual
+            })
+            .collect();
+        (levels, visual)
+    }
+
+    /// Checks that a shaper that only sees the concatenated output, and picks the paragraph
+    /// direction on its own, resolves the same levels and visual order as `spans` with
+    /// `base_level`, and that every span keeps its own characters.
+    fn assert_preserves_levels(spans: &[&str], base_level: Option<Level>) {
+        let spans: Vec<String> = spans.iter().map(|span| span.to_string()).collect();
+        let (encoded, _) = encode_bidi_levels(&spans, base_level);
+
+        for (span, encoded_span) in spans.iter().zip(&encoded) {
+            assert_eq!(span, &strip_bidi_controls(encoded_span));
+        }
+        assert_eq!(
+            resolve(&encoded.concat(), None),
+            resolve(&spans.concat(), base_level),
+            "{encoded:?}"
+        );
+    }
+
+    #[test]
+    fn single_span() {
+        assert_preserves_levels(&["hello عالم world"], Some(Level::ltr()));
+        assert_preserves_levels(&["hello عالم world"], Some(Level::rtl()));
+        assert_preserves_levels(&["مرحبا world عالم"], None);
+    }
+
+    #[test]
+    fn runs_across_span_boundaries() {
+        assert_preserves_levels(
+            &["One Two ", "Three, اربعة", " خمسة ستة", "، seven ", "eight"],
+            Some(Level::ltr()),
+        );
+        assert_preserves_levels(
+            &["واحد ", "إثنان (four", " five) ", "ستة"],
+            Some(Level::rtl()),
+        );
+        // Spans that start or end with neutrals, or are empty.
+        assert_preserves_levels(&["", "abc ", "(", "عربي", ")", " ", ""], Some(Level::rtl()));
+    }
+
+    #[test]
+    fn nested_runs_and_numbers() {
+        // Left-to-right text with numbers inside right-to-left text inside left-to-right text.
+        assert_preserves_levels(
+            &["The subject ", "\"قال hello 123 world ثم\"", " was sent"],
+            Some(Level::ltr()),
+        );
+        // Numbers directly in right-to-left text, next to Latin text and punctuation.
+        assert_preserves_levels(
+            &["الطلب رقم 5164134 ", "بتاريخ 2024-10-18", " (order 42, 43)"],
+            Some(Level::rtl()),
+        );
+        assert_preserves_levels(&["٣ رسائل", " from 12 users ", "١٢٣"], Some(Level::ltr()));
+        // The brackets take the paragraph direction, so the numbers are isolated apart from the
+        // Arabic letters that make them Arabic numbers, which are not joined by a hyphen.
+        assert_preserves_levels(&["(عمر) ", "5-3"], Some(Level::ltr()));
+    }
+
+    #[test]
+    fn several_paragraphs() {
+        let spans: Vec<&str> = MAIL_MESSAGE
+            .split_inclusive(|c: char| c.is_whitespace())
+            .collect();
+        for base_level in [None, Some(Level::ltr()), Some(Level::rtl())] {
+            assert_preserves_levels(&spans, base_level);
+        }
+        assert_preserves_levels(
+            &["English first\nثم ", "العربية\n", "mixed عربي text"],
+            None,
+        );
+    }
+
+    #[test]
+    fn auto_direction_resolves_each_paragraph() {
+        let spans = [
+            "English first\nثم ".to_string(),
+            "العربية\nback to English".to_string(),
+        ];
+        assert_eq!(
+            encode_bidi_levels(&spans, None).1,
+            [Level::ltr(), Level::rtl(), Level::ltr()]
+        );
+        assert_eq!(
+            encode_bidi_levels(&spans, Some(Level::rtl())).1,
+            [Level::rtl(); 3]
+        );
+        assert!(encode_bidi_levels(&[String::new()], None).1.is_empty());
+    }
+
+    #[test]
+    fn justifies_to_the_start_of_the_first_paragraph() {
+        let (ltr, rtl) = (Level::ltr(), Level::rtl());
+        assert_eq!(
+            paragraph_justify(&[ltr, ltr], rtl),
+            (JustifyText::Left, false)
+        );
+        assert_eq!(paragraph_justify(&[rtl], ltr), (JustifyText::Right, false));
+        assert_eq!(paragraph_justify(&[], rtl), (JustifyText::Right, false));
+        // Levels 0 and 2 are both left-to-right.
+        assert_eq!(
+            paragraph_justify(&[ltr, Level::new(2).unwrap()], ltr),
+            (JustifyText::Left, false)
+        );
+        assert_eq!(
+            paragraph_justify(&[ltr, rtl], ltr),
+            (JustifyText::Left, true)
+        );
+        assert_eq!(
+            paragraph_justify(&[rtl, ltr], ltr),
+            (JustifyText::Right, true)
+        );
+    }
+
+    /// Runs [`bidi_text_system`] on a text in an English UI and returns its justification and
+    /// text.
+    fn resolve_text(text: &str, text_direction: TextDirection) -> (JustifyText, String) {
+        let mut app = App::new();
+        app.insert_resource(locale_registry())
+            .insert_resource(CurrentLanguage("en".parse().unwrap()))
+            .add_systems(Update, bidi_text_system);
+        let entity = app
+            .world_mut()
+            .spawn((
+                Text::new(text),
+                TextLayout::default(),
+                BidiText,
+                text_direction,
+            ))
+            .id();
+        app.update();
+
+        let entity = app.world().entity(entity);
+        (
+            entity.get::<TextLayout>().unwrap().justify,
+            entity.get::<Text>().unwrap().0.clone(),
+        )
+    }
+
+    #[test]
+    fn auto_direction_justifies_mixed_paragraphs_by_the_first_one() {
+        let (justify, text) = resolve_text("English first\nثم العربية", TextDirection::Auto);
+        assert_eq!(justify, JustifyText::Left);
+        assert_eq!(text, "\u{200E}English first\n\u{200F}ثم العربية");
+
+        let (justify, text) = resolve_text("العربية أولا\nthen English", TextDirection::Auto);
+        assert_eq!(justify, JustifyText::Right);
+        assert_eq!(text, "\u{200F}العربية أولا\n\u{200E}then English");
+    }
+
+    #[test]
+    fn explicit_directions_override_the_text() {
+        assert_eq!(
+            resolve_text("العربية", TextDirection::Inherit).0,
+            JustifyText::Left
+        );
+        assert_eq!(
+            resolve_text("العربية", TextDirection::Ltr).0,
+            JustifyText::Left
+        );
+        assert_eq!(
+            resolve_text("English", TextDirection::Rtl).0,
+            JustifyText::Right
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Calendar systems that dates can be shown in.
+//!
+//! The Hijri calendar here is the Umm al-Qura calendar of Saudi Arabia, whose months follow the
+//! published table of month lengths for 1300 to 1600 AH. Dates outside of the table fall back to
+//! the arithmetical (tabular) Islamic calendar, with the civil epoch and the 30-year leap cycle
+//! used by most software, whose months start up to a day or two apart from the observed ones.
+
+use chrono::{Datelike, NaiveDate};
+
+/// Which calendars a locale shows dates in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CalendarDisplay {
+    #[default]
+    Gregorian,
+    Hijri,
+    /// The Gregorian date, followed by the Hijri date.
+    Both,
+}
+
+/// Day count of 1 Muharram 1 AH, Friday 16 July 622 in the Julian calendar, counting 1 January
+/// 1 CE in the proleptic Gregorian calendar as day 1.
+const HIJRI_EPOCH: i64 = 227_015;
+
+/// The first year of [`UMM_AL_QURA_MONTHS`].
+const UMM_AL_QURA_FIRST_YEAR: i32 = 1300;
+
+/// Day count of 1 Muharram 1300, 12 November 1882, in the same count as [`HIJRI_EPOCH`].
+const UMM_AL_QURA_EPOCH: i64 = 687_337;
+
+/// The lengths of the months of the Umm al-Qura calendar, one entry per year from
+/// [`UMM_AL_QURA_FIRST_YEAR`] to 1600 AH. Bit `n` is set when month `n + 1` has 30 days rather
+/// than 29, so bit 0 is Muharram.
+#[rustfmt::skip]
+const UMM_AL_QURA_MONTHS: [u16; 301] = [
+    0x555, 0x2AB, 0x937, 0x2B6, 0x576, 0x36C, 0xB55, 0xAAA, 0x956, 0x49E,
+    0x95D, 0x2BA, 0x5B5, 0x3AA, 0xB4B, 0xA96, 0x52E, 0x2AD, 0x56D, 0xB5A,
+    0x752, 0xF25, 0xE8A, 0xD16, 0xA56, 0xAB5, 0x6B4, 0xDA9, 0xB92, 0xB25,
+    0x64B, 0xA9B, 0x35A, 0x6D9, 0x5D4, 0xDA5, 0xD4A, 0xA95, 0x536, 0x975,
+    0x2F4, 0x6E9, 0x6D4, 0x6A9, 0x535, 0x25D, 0x4BD, 0x9BA, 0x3B4, 0xB69,
+    0xB2A, 0xA55, 0x4AD, 0xA5D, 0x2DA, 0x6D9, 0xEAA, 0xE94, 0xD2A, 0xC56,
+    0x4AE, 0xA6D, 0x56A, 0xD55, 0xD4A, 0xA93, 0x52B, 0xA5B, 0x53A, 0x6B5,
+    0xEA9, 0xD52, 0xD29, 0xA55, 0x4AD, 0x56D, 0xAEA, 0x6E4, 0xED1, 0xDA2,
+    0xAAA, 0x95A, 0x2DA, 0x5B9, 0xBB2, 0x764, 0x6C9, 0x555, 0x2AB, 0x4DB,
+    0xABA, 0x5B4, 0xDA9, 0xD52, 0xAA5, 0x92D, 0x26D, 0x8ED, 0x2DA, 0xAD5,
+    0xAA5, 0xA4B, 0x497, 0x937, 0x2B6, 0x975, 0xD69, 0xD52, 0xC95, 0x92B,
+    0x25B, 0x4DB, 0x9D5, 0x5D2, 0xDA5, 0xD4A, 0xA95, 0x54D, 0xAAD, 0x3AA,
+    0xBD2, 0xBC4, 0xB89, 0xA95, 0x52D, 0x5AD, 0xB6A, 0x6D4, 0xDC9, 0xD92,
+    0xAA6, 0x956, 0x2AE, 0x56D, 0x36A, 0xB55, 0xAAA, 0x94D, 0x49D, 0x95D,
+    0x2BA, 0x5B5, 0x5AA, 0xD55, 0xA9A, 0x92E, 0x26E, 0x55D, 0xADA, 0x6D4,
+    0x6A5, 0xB27, 0xA4D, 0x4AD, 0x56D, 0xB5A, 0x754, 0xF49, 0xE92, 0xD26,
+    0xA56, 0x356, 0x6B5, 0xBAA, 0xB92, 0xB25, 0x68B, 0xA9B, 0x55A, 0xADA,
+    0x5B4, 0xDA9, 0xB52, 0xA9A, 0x536, 0x276, 0x575, 0xAF2, 0x6D4, 0x6A9,
+    0x555, 0x2AD, 0x4BD, 0x9BA, 0x574, 0xB69, 0xB52, 0xA95, 0x52D, 0xA5D,
+    0x4DA, 0xAD9, 0x6B2, 0xE95, 0xE2A, 0xC96, 0x92E, 0xAAD, 0x56A, 0xD65,
+    0xD4A, 0xD15, 0x62B, 0xC5B, 0x53A, 0x6B5, 0xDB2, 0xD64, 0xD29, 0xA55,
+    0x4AD, 0x96D, 0xAEA, 0x6E8, 0xED1, 0xDA4, 0xD4A, 0xA6A, 0x2DA, 0x5B9,
+    0xB72, 0xB68, 0x6D1, 0x655, 0x4AB, 0x95B, 0x2BA, 0x5B5, 0xDA9, 0xD52,
+    0xCA6, 0x94E, 0x46E, 0x95D, 0x4DA, 0xAD5, 0xAAA, 0xA4D, 0x49B, 0x937,
+    0x4B6, 0x975, 0xD6A, 0xD52, 0xAA5, 0x94B, 0x2AB, 0x55B, 0xAD9, 0x5D2,
+    0xDC5, 0xD92, 0xB25, 0x555, 0xAB5, 0x5B4, 0xBA9, 0x7A2, 0x745, 0x593,
+    0xAAB, 0x4D6, 0x9D6, 0x5D2, 0xBA5, 0xB4A, 0xA95, 0x4AD, 0x15D, 0x2DD,
+    0x9DA, 0x5B4, 0x5A9, 0x52D, 0x25B, 0x8B7, 0x176, 0x56D, 0xB6A, 0xACA,
+    0xA96, 0x52B, 0x15B, 0x2BB, 0x5B6, 0xDAA, 0xB94, 0xD46, 0xA8D, 0x52D,
+    0xA9D, 0x55A, 0x755, 0x749, 0xF13, 0xE4A, 0xA96, 0x556, 0x6B5, 0xBAA,
+    0xB94,
+];
+
+fn month_length(months: u16, month: u32) -> i64 {
+    29 + (months >> (month - 1) & 1) as i64
+}
+
+fn year_length(months: u16) -> i64 {
+    12 * 29 + months.count_ones() as i64
+}
+
+/// Returns the month lengths of `year` and the day count of its 1 Muharram, if the year is in
+/// [`UMM_AL_QURA_MONTHS`].
+#[cfg(test)]
+fn umm_al_qura_year(year: i32) -> Option<(u16, i64)> {
+    let index = usize::try_from(year - UMM_AL_QURA_FIRST_YEAR).ok()?;
+    let months = *UMM_AL_QURA_MONTHS.get(index)?;
+    let new_year = UMM_AL_QURA_EPOCH
+        + UMM_AL_QURA_MONTHS[..index]
+            .iter()
+            .map(|&months| year_length(months))
+            .sum::<i64>();
+    Some((months, new_year))
+}
+
+/// A date in the Hijri calendar.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HijriDate {
+    pub year: i32,
+    /// From 1 for Muharram to 12 for Dhu al-Hijjah.
+    pub month: u32,
+    pub day: u32,
+}
+
+impl HijriDate {
+    /// Returns the day count of this date, in the same count as [`HIJRI_EPOCH`].
+    #[cfg(test)]
+    fn days_from_ce(self) -> i64 {
+        match umm_al_qura_year(self.year) {
+            Some((months, new_year)) => {
+                new_year
+                    + (1..self.month)
+                        .map(|month| month_length(months, month))
+                        .sum::<i64>()
+                    + self.day as i64
+                    - 1
+            }
+            None => self.tabular_days_from_ce(),
+        }
+    }
+
+    /// Returns the day count of this date in the tabular Islamic calendar.
+    fn tabular_days_from_ce(self) -> i64 {
+        let year = self.year as i64;
+        let month = self.month as i64;
+        // Leap years add a day to Dhu al-Hijjah, 11 times every 30 years.
+        HIJRI_EPOCH - 1
+            + (year - 1) * 354
+            + (3 + 11 * year).div_euclid(30)
+            + 29 * (month - 1)
+            + month / 2
+            + self.day as i64
+    }
+
+    pub fn from_gregorian(date: NaiveDate) -> Self {
+        let days = date.num_days_from_ce() as i64;
+        if days < UMM_AL_QURA_EPOCH {
+            return Self::from_tabular_days(days);
+        }
+        let mut year_start = UMM_AL_QURA_EPOCH;
+        for (year, &months) in (UMM_AL_QURA_FIRST_YEAR..).zip(&UMM_AL_QURA_MONTHS) {
+            if days >= year_start + year_length(months) {
+                year_start += year_length(months);
+                continue;
+            }
+            let mut month_start = year_start;
+            let mut month = 1;
+            while days >= month_start + month_length(months, month) {
+                month_start += month_length(months, month);
+                month += 1;
+            }
+            return Self {
+                year,
+                month,
+                day: (days - month_start + 1) as u32,
+            };
+        }
+        Self::from_tabular_days(days)
+    }
+
+    /// Returns the date of day count `days` in the tabular Islamic calendar.
+    fn from_tabular_days(days: i64) -> Self {
+        let year = (30 * (days - HIJRI_EPOCH) + 10646).div_euclid(10631) as i32;
+        let new_year = Self {
+            year,
+            month: 1,
+            day: 1,
+        }
+        .tabular_days_from_ce();
+        let month = ((11 * (days - new_year) + 330).div_euclid(325)).min(12) as u32;
+        let first_of_month = Self {
+            year,
+            month,
+            day: 1,
+        }
+        .tabular_days_from_ce();
+        Self {
+            year,
+            month,
+            day: (days - first_of_month + 1) as u32,
+        }
+    }
+
+    /// Returns the Gregorian date of this date, or `None` if it is out of range.
+    #[cfg(test)]
+    pub fn to_gregorian(self) -> Option<NaiveDate> {
+        let days = i32::try_from(self.days_from_ce()).ok()?;
+        NaiveDate::from_num_days_from_ce_opt(days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hijri(year: i32, month: u32, day: u32) -> HijriDate {
+        HijriDate { year, month, day }
+    }
+
+    fn gregorian(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn umm_al_qura_dates() {
+        for (date, hijri) in [
+            (gregorian(1882, 11, 12), hijri(1300, 1, 1)),
+            (gregorian(2022, 4, 2), hijri(1443, 9, 1)),
+            (gregorian(2023, 4, 21), hijri(1444, 10, 1)),
+            (gregorian(2024, 6, 7), hijri(1445, 12, 1)),
+            (gregorian(2025, 3, 30), hijri(1446, 10, 1)),
+            (gregorian(2026, 10, 18), hijri(1448, 5, 7)),
+            (gregorian(2174, 11, 25), hijri(1600, 12, 30)),
+        ] {
+            assert_eq!(HijriDate::from_gregorian(date), hijri, "{date}");
+            assert_eq!(hijri.to_gregorian(), Some(date), "{hijri:?}");
+        }
+    }
+
+    #[test]
+    fn tabular_dates_outside_of_the_table() {
+        // 16 July 622 in the Julian calendar.
+        assert_eq!(
+            HijriDate::from_gregorian(gregorian(622, 7, 19)),
+            hijri(1, 1, 1)
+        );
+        assert_eq!(
+            HijriDate::from_gregorian(gregorian(1882, 11, 11)),
+            hijri(1299, 12, 29)
+        );
+        assert_eq!(
+            HijriDate::from_gregorian(gregorian(2174, 11, 26)),
+            hijri(1601, 1, 1)
+        );
+    }
+
+    #[test]
+    fn round_trips_every_day() {
+        let mut previous = HijriDate::from_gregorian(gregorian(1850, 1, 1));
+        for date in gregorian(1850, 1, 2)
+            .iter_days()
+            .take_while(|date| date.year() < 2250)
+        {
+            let hijri = HijriDate::from_gregorian(date);
+            assert_eq!(hijri.to_gregorian(), Some(date), "{hijri:?}");
+            // Every day is the next day of the previous one.
+            let next_day = hijri.day == previous.day + 1 && hijri.month == previous.month;
+            let next_month = hijri.day == 1
+                && (29..=30).contains(&previous.day)
+                && (hijri.month == previous.month + 1
+                    || (hijri.month, previous.month, hijri.year) == (1, 12, previous.year + 1));
+            assert!(next_day || next_month, "{previous:?} then {hijri:?}");
+            previous = hijri;
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! Date and time formatting with CLDR-style patterns, like "MMM d, h:mm a".
+//!
+//! See <https://unicode.org/reports/tr35/tr35-dates.html#Date_Field_Symbol_Table> for the
+//! pattern syntax. Only the fields used by [`DateTimeStyle`] are supported: `G`, `y`, `M`,
+//! `d`, `E`, `a`, `h`, `H`, `m` and `s`.
+
+use crate::{
+    calendar::{CalendarDisplay, HijriDate},
+    language_identifier::LanguageIdentifier,
+    locale::{DigitPreference, DigitSystem},
+    CurrentLanguage, LocaleRegistry,
+};
+use bevy::prelude::*;
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// The names and patterns a language uses to write dates and times.
+struct DateTimeSymbols {
+    months: [&'static str; 12],
+    abbreviated_months: [&'static str; 12],
+    /// Starting with Sunday.
+    weekdays: [&'static str; 7],
+    abbreviated_weekdays: [&'static str; 7],
+    /// AM and PM.
+    day_periods: [&'static str; 2],
+    hijri_months: [&'static str; 12],
+    hijri_abbreviated_months: [&'static str; 12],
+    /// The era of the Gregorian and of the Hijri calendar.
+    eras: [&'static str; 2],
+    short_date: &'static str,
+    long_date: &'static str,
+    hijri_short_date: &'static str,
+    hijri_long_date: &'static str,
+    month_day: &'static str,
+    time: &'static str,
+    /// Joins a date pattern, `{1}`, with a time pattern, `{0}`.
+    date_time: &'static str,
+}
+
+const ENGLISH: DateTimeSymbols = DateTimeSymbols {
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    abbreviated_months: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    weekdays: [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ],
+    abbreviated_weekdays: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    day_periods: ["AM", "PM"],
+    hijri_months: [
+        "Muharram",
+        "Safar",
+        "Rabiʻ I",
+        "Rabiʻ II",
+        "Jumada I",
+        "Jumada II",
+        "Rajab",
+        "Shaʻban",
+        "Ramadan",
+        "Shawwal",
+        "Dhuʻl-Qiʻdah",
+        "Dhuʻl-Hijjah",
+    ],
+    hijri_abbreviated_months: [
+        "Muh.",
+        "Saf.",
+        "Rab. I",
+        "Rab. II",
+        "Jum. I",
+        "Jum. II",
+        "Raj.",
+        "Sha.",
+        "Ram.",
+        "Shaw.",
+        "Dhuʻl-Q.",
+        "Dhuʻl-H.",
+    ],
+    eras: ["AD", "AH"],
+    short_date: "M/d/yy",
+    long_date: "MMMM d, y",
+    hijri_short_date: "M/d/y G",
+    hijri_long_date: "MMMM d, y G",
+    month_day: "MMM d",
+    time: "h:mm a",
+    date_time: "{1}, {0}",
+};
+
+const ARABIC_MONTHS: [&str; 12] = [
+    "يناير",
+    "فبراير",
+    "مارس",
+    "أبريل",
+    "مايو",
+    "يونيو",
+    "يوليو",
+    "أغسطس",
+    "سبتمبر",
+    "أكتوبر",
+    "نوفمبر",
+    "ديسمبر",
+];
+
+const ARABIC_HIJRI_MONTHS: [&str; 12] = [
+    "محرم",
+    "صفر",
+    "ربيع الأول",
+    "ربيع الآخر",
+    "جمادى الأولى",
+    "جمادى الآخرة",
+    "رجب",
+    "شعبان",
+    "رمضان",
+    "شوال",
+    "ذو القعدة",
+    "ذو الحجة",
+];
+
+const ARABIC_WEEKDAYS: [&str; 7] = [
+    "الأحد",
+    "الاثنين",
+    "الثلاثاء",
+    "الأربعاء",
+    "الخميس",
+    "الجمعة",
+    "السبت",
+];
+
+const ARABIC: DateTimeSymbols = DateTimeSymbols {
+    months: ARABIC_MONTHS,
+    abbreviated_months: ARABIC_MONTHS,
+    weekdays: ARABIC_WEEKDAYS,
+    abbreviated_weekdays: ARABIC_WEEKDAYS,
+    day_periods: ["ص", "م"],
+    hijri_months: ARABIC_HIJRI_MONTHS,
+    hijri_abbreviated_months: ARABIC_HIJRI_MONTHS,
+    eras: ["م", "هـ"],
+    // The right-to-left marks keep the day first when the digits are Latin.
+    short_date: "d\u{200F}/M\u{200F}/y",
+    long_date: "d MMMM y",
+    hijri_short_date: "d\u{200F}/M\u{200F}/y G",
+    hijri_long_date: "d MMMM y G",
+    month_day: "d MMM",
+    time: "h:mm a",
+    date_time: "{1}، {0}",
+};
+
+fn symbols(language: &LanguageIdentifier) -> &'static DateTimeSymbols {
+    match language.language() {
+        "ar" => &ARABIC,
+        _ => &ENGLISH,
+    }
+}
+
+/// How much of a date and time to show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DateTimeStyle {
+    /// "10/18/26" in English, "١٨‏/١٠‏/٢٠٢٦" in Arabic.
+    ShortDate,
+    /// "October 18, 2026" in English, "١٨ أكتوبر ٢٠٢٦" in Arabic.
+    LongDate,
+    /// "9:05 PM" in English, "٩:٠٥ م" in Arabic.
+    Time,
+    /// "Oct 18, 9:05 PM" in English, "١٨ أكتوبر، ٩:٠٥ م" in Arabic.
+    MonthDayTime,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Calendar {
+    Gregorian,
+    Hijri,
+}
+
+/// Formats `datetime` in `language`, writing dates in `calendars` and numbers with `digits`.
+///
+/// When both calendars are shown, the Hijri date follows the Gregorian one in parentheses, e.g.
+/// "Oct 18 (Jum. I 7), 9:05 PM".
+pub fn format_datetime(
+    datetime: &NaiveDateTime,
+    style: DateTimeStyle,
+    language: &LanguageIdentifier,
+    digits: DigitSystem,
+    calendars: CalendarDisplay,
+) -> String {
+    let symbols = symbols(language);
+    let format = |pattern, calendar| format_pattern(datetime, calendar, pattern, symbols, digits);
+    let date_pattern = |calendar| match (style, calendar) {
+        (DateTimeStyle::ShortDate, Calendar::Gregorian) => Some(symbols.short_date),
+        (DateTimeStyle::ShortDate, Calendar::Hijri) => Some(symbols.hijri_short_date),
+        (DateTimeStyle::LongDate, Calendar::Gregorian) => Some(symbols.long_date),
+        (DateTimeStyle::LongDate, Calendar::Hijri) => Some(symbols.hijri_long_date),
+        (DateTimeStyle::Time, _) => None,
+        (DateTimeStyle::MonthDayTime, _) => Some(symbols.month_day),
+    };
+    let time_pattern = match style {
+        DateTimeStyle::Time | DateTimeStyle::MonthDayTime => Some(symbols.time),
+        DateTimeStyle::ShortDate | DateTimeStyle::LongDate => None,
+    };
+
+    let gregorian =
+        date_pattern(Calendar::Gregorian).map(|pattern| format(pattern, Calendar::Gregorian));
+    let hijri = date_pattern(Calendar::Hijri).map(|pattern| format(pattern, Calendar::Hijri));
+    let date = match calendars {
+        CalendarDisplay::Gregorian => gregorian,
+        CalendarDisplay::Hijri => hijri,
+        CalendarDisplay::Both => gregorian
+            .zip(hijri)
+            .map(|(gregorian, hijri)| format!("{gregorian} ({hijri})")),
+    };
+    let time = time_pattern.map(|pattern| format(pattern, Calendar::Gregorian));
+
+    match (date, time) {
+        (Some(date), Some(time)) => symbols
+            .date_time
+            .replace("{1}", &date)
+            .replace("{0}", &time),
+        (Some(text), None) | (None, Some(text)) => text,
+        (None, None) => String::new(),
+    }
+}
+
+fn format_pattern(
+    datetime: &NaiveDateTime,
+    calendar: Calendar,
+    pattern: &str,
+    symbols: &DateTimeSymbols,
+    digits: DigitSystem,
+) -> String {
+    let number = |value: i64, width: usize| digits.shape(&format!("{value:0width$}"));
+    let (year, month, day, months, abbreviated_months) = match calendar {
+        Calendar::Gregorian => (
+            datetime.year(),
+            datetime.month(),
+            datetime.day(),
+            &symbols.months,
+            &symbols.abbreviated_months,
+        ),
+        Calendar::Hijri => {
+            let date = HijriDate::from_gregorian(datetime.date());
+            (
+                date.year,
+                date.month,
+                date.day,
+                &symbols.hijri_months,
+                &symbols.hijri_abbreviated_months,
+            )
+        }
+    };
+    let weekday = datetime.weekday().num_days_from_sunday() as usize;
+    let (is_pm, hour12) = datetime.hour12();
+    let mut output = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Text between single quotes is literal, and two single quotes are one quote.
+        if c == '\'' {
+            if chars.next_if_eq(&'\'').is_some() {
+                output.push('\'');
+                continue;
+            }
+            while let Some(c) = chars.next() {
+                if c != '\'' {
+                    output.push(c);
+                } else if chars.next_if_eq(&'\'').is_some() {
+                    output.push('\'');
+                } else {
+                    break;
+                }
+            }
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            output.push(c);
+            continue;
+        }
+
+        let mut width = 1;
+        while chars.next_if_eq(&c).is_some() {
+            width += 1;
+        }
+        match (c, width) {
+            ('G', _) => output.push_str(symbols.eras[calendar as usize]),
+            ('y', 2) => output.push_str(&number(year.rem_euclid(100) as i64, 2)),
+            ('y', _) => output.push_str(&number(year as i64, width)),
+            ('M', 1..=2) => output.push_str(&number(month as i64, width)),
+            ('M', 3) => output.push_str(abbreviated_months[month as usize - 1]),
+            ('M', _) => output.push_str(months[month as usize - 1]),
+            ('d', _) => output.push_str(&number(day as i64, width)),
+            ('E', 1..=3) => output.push_str(symbols.abbreviated_weekdays[weekday]),
+            ('E', _) => output.push_str(symbols.weekdays[weekday]),
+            ('a', _) => output.push_str(symbols.day_periods[is_pm as usize]),
+            ('h', _) => output.push_str(&number(hour12 as i64, width)),
+            ('H', _) => output.push_str(&number(datetime.hour() as i64, width)),
+            ('m', _) => output.push_str(&number(datetime.minute() as i64, width)),
+            ('s', _) => output.push_str(&number(datetime.second() as i64, width)),
+            _ => output.extend(std::iter::repeat_n(c, width)),
+        }
+    }
+    output
+}
+
+/// A date and time shown in the entity's `Text`, formatted for the current locale.
+#[derive(Component)]
+pub struct LocalizedDateTime {
+    pub datetime: NaiveDateTime,
+    pub style: DateTimeStyle,
+}
+
+/// Re-renders every [`LocalizedDateTime`] when it changes or the language or digits change.
+pub fn localize_datetime_system(
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    digit_preference: Res<DigitPreference>,
+    mut text_query: Query<(Ref<LocalizedDateTime>, &mut Text)>,
+) {
+    let changed = current_language.is_changed() || digit_preference.is_changed();
+    let locale = locale_registry.get(&current_language.0);
+    let digits = digit_preference.digits(locale);
+    let calendars = locale.map_or_else(CalendarDisplay::default, |locale| locale.calendar);
+    for (localized_datetime, mut text) in &mut text_query {
+        if !(changed || localized_datetime.is_changed()) {
+            continue;
+        }
+        let formatted = format_datetime(
+            &localized_datetime.datetime,
+            localized_datetime.style,
+            &current_language.0,
+            digits,
+            calendars,
+        );
+        if text.0 != formatted {
+            text.0 = formatted;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Sunday 18 October 2026, 7 Jumada I 1448, at 9:05 PM, the date of the examples.
+    fn example() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(21, 5, 0)
+            .unwrap()
+    }
+
+    fn format(
+        style: DateTimeStyle,
+        language: &str,
+        digits: DigitSystem,
+        calendars: CalendarDisplay,
+    ) -> String {
+        format_datetime(
+            &example(),
+            style,
+            &language.parse().unwrap(),
+            digits,
+            calendars,
+        )
+    }
+
+    /// Formats the example in every style, in the Gregorian calendar.
+    fn format_styles(language: &str, digits: DigitSystem) -> [String; 4] {
+        [
+            DateTimeStyle::ShortDate,
+            DateTimeStyle::LongDate,
+            DateTimeStyle::Time,
+            DateTimeStyle::MonthDayTime,
+        ]
+        .map(|style| format(style, language, digits, CalendarDisplay::Gregorian))
+    }
+
+    #[test]
+    fn english_examples() {
+        assert_eq!(
+            format_styles("en", DigitSystem::Latin),
+            ["10/18/26", "October 18, 2026", "9:05 PM", "Oct 18, 9:05 PM"]
+        );
+        assert_eq!(
+            format_styles("en", DigitSystem::ArabicIndic),
+            ["١٠/١٨/٢٦", "October ١٨, ٢٠٢٦", "٩:٠٥ PM", "Oct ١٨, ٩:٠٥ PM"]
+        );
+    }
+
+    #[test]
+    fn arabic_examples() {
+        assert_eq!(
+            format_styles("ar", DigitSystem::ArabicIndic),
+            [
+                "١٨\u{200F}/١٠\u{200F}/٢٠٢٦",
+                "١٨ أكتوبر ٢٠٢٦",
+                "٩:٠٥ م",
+                "١٨ أكتوبر، ٩:٠٥ م"
+            ]
+        );
+        assert_eq!(
+            format_styles("ar", DigitSystem::Latin),
+            [
+                "18\u{200F}/10\u{200F}/2026",
+                "18 أكتوبر 2026",
+                "9:05 م",
+                "18 أكتوبر، 9:05 م"
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_languages_use_english() {
+        assert_eq!(
+            format_styles("fr", DigitSystem::Latin),
+            format_styles("en", DigitSystem::Latin)
+        );
+    }
+
+    #[test]
+    fn hijri_dates() {
+        let hijri =
+            |style, language, digits| format(style, language, digits, CalendarDisplay::Hijri);
+        assert_eq!(
+            hijri(DateTimeStyle::ShortDate, "en", DigitSystem::Latin),
+            "5/7/1448 AH"
+        );
+        assert_eq!(
+            hijri(DateTimeStyle::LongDate, "en", DigitSystem::Latin),
+            "Jumada I 7, 1448 AH"
+        );
+        assert_eq!(
+            hijri(DateTimeStyle::MonthDayTime, "en", DigitSystem::Latin),
+            "Jum. I 7, 9:05 PM"
+        );
+        assert_eq!(
+            hijri(DateTimeStyle::ShortDate, "ar", DigitSystem::ArabicIndic),
+            "٧\u{200F}/٥\u{200F}/١٤٤٨ هـ"
+        );
+        assert_eq!(
+            hijri(DateTimeStyle::LongDate, "ar", DigitSystem::ArabicIndic),
+            "٧ جمادى الأولى ١٤٤٨ هـ"
+        );
+        // Times do not depend on the calendar.
+        assert_eq!(
+            hijri(DateTimeStyle::Time, "ar", DigitSystem::ArabicIndic),
+            "٩:٠٥ م"
+        );
+    }
+
+    #[test]
+    fn both_calendars() {
+        let both = |style, language, digits| format(style, language, digits, CalendarDisplay::Both);
+        assert_eq!(
+            both(DateTimeStyle::MonthDayTime, "en", DigitSystem::Latin),
+            "Oct 18 (Jum. I 7), 9:05 PM"
+        );
+        assert_eq!(
+            both(DateTimeStyle::LongDate, "en", DigitSystem::Latin),
+            "October 18, 2026 (Jumada I 7, 1448 AH)"
+        );
+        assert_eq!(
+            both(DateTimeStyle::ShortDate, "en", DigitSystem::Latin),
+            "10/18/26 (5/7/1448 AH)"
+        );
+        assert_eq!(
+            both(DateTimeStyle::Time, "en", DigitSystem::Latin),
+            "9:05 PM"
+        );
+        assert_eq!(
+            both(DateTimeStyle::MonthDayTime, "ar", DigitSystem::ArabicIndic),
+            "١٨ أكتوبر (٧ جمادى الأولى)، ٩:٠٥ م"
+        );
+        assert_eq!(
+            both(DateTimeStyle::LongDate, "ar", DigitSystem::Latin),
+            "18 أكتوبر 2026 (7 جمادى الأولى 1448 هـ)"
+        );
+    }
+
+    #[test]
+    fn pattern_fields() {
+        let format = |pattern| {
+            format_pattern(
+                &example(),
+                Calendar::Gregorian,
+                pattern,
+                &ENGLISH,
+                DigitSystem::Latin,
+            )
+        };
+        assert_eq!(format("E EEEE"), "Sun Sunday");
+        assert_eq!(format("HH:mm:ss"), "21:05:00");
+        assert_eq!(format("hh a"), "09 PM");
+        assert_eq!(format("d/MM/yyyy G"), "18/10/2026 AD");
+        assert_eq!(format("MMM MMMM"), "Oct October");
+    }
+
+    #[test]
+    fn pattern_literals() {
+        let format = |pattern| {
+            format_pattern(
+                &example(),
+                Calendar::Gregorian,
+                pattern,
+                &ENGLISH,
+                DigitSystem::ArabicIndic,
+            )
+        };
+        assert_eq!(format("h 'o''clock'"), "٩ o'clock");
+        assert_eq!(format("''h''"), "'٩'");
+        assert_eq!(format("'at' h 'h' m"), "at ٩ h ٥");
+        // Unquoted letters that are not fields are kept as they are.
+        assert_eq!(format("QQ h"), "QQ ٩");
+    }
+}
@@ -0,0 +1,339 @@
+//! Inline direction of UI nodes, inherited from parent to child.
+//!
+//! Root nodes inherit the direction of the current locale. Any node can override the
+//! direction of its subtree with a [`LayoutDirection`], e.g. to keep a code block
+//! left-to-right inside the Arabic UI.
+//!
+//! The inherited direction is kept in [`ComputedLayoutDirection`], which logical properties
+//! like [`LogicalStyle`](crate::logical::LogicalStyle) resolve against, and copied to
+//! `Style::direction` on the containers whose layout reads it. Only the subtrees whose direction
+//! can have changed are walked, and a language switch stops at subtrees that override it.
+
+use crate::{CurrentLanguage, LocaleRegistry};
+use bevy::{prelude::*, utils::HashSet};
+
+/// The inline direction of a node and, unless they override it, of its descendants.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LayoutDirection {
+    /// Takes the direction of the parent node, or of the current locale for root nodes.
+    #[default]
+    Inherit,
+    Ltr,
+    Rtl,
+}
+
+/// The direction a node ends up with after inheritance.
+///
+/// Added to every UI node by [`propagate_layout_direction_system`], which also copies it to
+/// `Style::direction` on the nodes that need it, see [`lays_out_in_direction`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ComputedLayoutDirection(pub Direction);
+
+impl ComputedLayoutDirection {
+    pub fn is_rtl(&self) -> bool {
+        self.0 == Direction::Rtl
+    }
+}
+
+type DirectionNode<'a> = (
+    Option<&'a LayoutDirection>,
+    Option<&'a mut ComputedLayoutDirection>,
+    &'a mut Style,
+    Option<&'a Children>,
+);
+
+/// Updates the direction of the nodes whose inherited direction may have changed: every node
+/// when the language changes, or else the nodes that were added, moved or given another
+/// [`LayoutDirection`], and their descendants.
+pub fn propagate_layout_direction_system(
+    mut commands: Commands,
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    root_query: Query<Entity, (With<Node>, Without<Parent>)>,
+    changed_query: Query<
+        Entity,
+        (
+            With<Node>,
+            Or<(
+                Changed<LayoutDirection>,
+                Changed<Children>,
+                Changed<Parent>,
+                Added<Node>,
+            )>,
+        ),
+    >,
+    parent_query: Query<&Parent>,
+    mut node_query: Query<DirectionNode, With<Node>>,
+) {
+    let locale_changed = current_language.is_changed() || locale_registry.is_changed();
+    // The changed nodes and their ancestors, which lead to them from a root.
+    let mut dirty = HashSet::new();
+    for entity in &changed_query {
+        for entity in std::iter::once(entity).chain(parent_query.iter_ancestors(entity)) {
+            if !dirty.insert(entity) {
+                break;
+            }
+        }
+    }
+    if !locale_changed && dirty.is_empty() {
+        return;
+    }
+
+    let locale_direction = locale_registry.direction(&current_language.0);
+    for root in &root_query {
+        if locale_changed || dirty.contains(&root) {
+            propagate(
+                root,
+                locale_direction,
+                &dirty,
+                &mut commands,
+                &mut node_query,
+            );
+        }
+    }
+}
+
+/// Updates the direction of `entity`, then of its children whose direction may have changed:
+/// all of them if the direction of `entity` changed, or else the `dirty` ones.
+fn propagate(
+    entity: Entity,
+    parent_direction: Direction,
+    dirty: &HashSet<Entity>,
+    commands: &mut Commands,
+    node_query: &mut Query<DirectionNode, With<Node>>,
+) {
+    let Ok((layout_direction, computed_direction, mut style, children)) =
+        node_query.get_mut(entity)
+    else {
+        return;
+    };
+
+    let direction = match layout_direction.copied().unwrap_or_default() {
+        LayoutDirection::Inherit => parent_direction,
+        LayoutDirection::Ltr => Direction::Ltr,
+        LayoutDirection::Rtl => Direction::Rtl,
+    };
+
+    if lays_out_in_direction(children) && style.direction != direction {
+        style.direction = direction;
+    }
+    let changed = match computed_direction {
+        Some(mut computed_direction) => {
+            let changed = computed_direction.0 != direction;
+            if changed {
+                computed_direction.0 = direction;
+            }
+            changed
+        }
+        None => {
+            commands
+                .entity(entity)
+                .insert(ComputedLayoutDirection(direction));
+            true
+        }
+    };
+
+    let children: Vec<Entity> = children.map_or_else(Vec::new, |children| children.to_vec());
+    for child in children {
+        if changed || dirty.contains(&child) {
+            propagate(child, direction, dirty, commands, node_query);
+        }
+    }
+}
+
+/// Whether the layout of a node depends on `Style::direction`: flex rows, flex columns and
+/// grids all place their children from the inline start. Leaf nodes follow their direction
+/// through logical properties only.
+fn lays_out_in_direction(children: Option<&Children>) -> bool {
+    children.is_some_and(|children| !children.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::tests::locale_registry;
+
+    struct Tree {
+        app: App,
+        root: Entity,
+        code_block: Entity,
+        code: Entity,
+        label: Entity,
+    }
+
+    /// Spawns a row with a left-to-right code block and a label, in `language`.
+    fn spawn_tree(language: &str) -> Tree {
+        let mut app = App::new();
+        app.insert_resource(locale_registry())
+            .insert_resource(CurrentLanguage(language.parse().unwrap()))
+            .add_systems(Update, propagate_layout_direction_system);
+        let world = app.world_mut();
+        let code = world.spawn(NodeBundle::default()).id();
+        let code_block = world
+            .spawn((NodeBundle::default(), LayoutDirection::Ltr))
+            .add_child(code)
+            .id();
+        let label = world.spawn(NodeBundle::default()).id();
+        let root = world
+            .spawn(NodeBundle::default())
+            .add_children(&[code_block, label])
+            .id();
+        app.update();
+        Tree {
+            app,
+            root,
+            code_block,
+            code,
+            label,
+        }
+    }
+
+    fn computed_direction(app: &App, entity: Entity) -> Direction {
+        app.world()
+            .get::<ComputedLayoutDirection>(entity)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn overrides_apply_to_their_subtree() {
+        let tree = spawn_tree("ar");
+        let direction = |entity| computed_direction(&tree.app, entity);
+        assert_eq!(direction(tree.root), Direction::Rtl);
+        assert_eq!(direction(tree.label), Direction::Rtl);
+        assert_eq!(direction(tree.code_block), Direction::Ltr);
+        assert_eq!(direction(tree.code), Direction::Ltr);
+    }
+
+    #[test]
+    fn language_switch_only_writes_the_styles_that_need_it() {
+        let mut tree = spawn_tree("ar");
+        let world = tree.app.world();
+        assert_eq!(
+            world.get::<Style>(tree.root).unwrap().direction,
+            Direction::Rtl
+        );
+        // The label has no children, so it does not need a physical direction.
+        assert_eq!(
+            world.get::<Style>(tree.label).unwrap().direction,
+            Style::default().direction
+        );
+
+        let before_switch = tree.app.world().read_change_tick();
+        tree.app.world_mut().resource_mut::<CurrentLanguage>().0 = "en".parse().unwrap();
+        tree.app.update();
+
+        let world = tree.app.world();
+        let this_run = world.read_change_tick();
+        let style_changed = |entity| {
+            world
+                .entity(entity)
+                .get_ref::<Style>()
+                .unwrap()
+                .last_changed()
+                .is_newer_than(before_switch, this_run)
+        };
+        assert!(style_changed(tree.root));
+        assert!(!style_changed(tree.label));
+        assert!(!style_changed(tree.code_block));
+        assert!(!style_changed(tree.code));
+        assert_eq!(computed_direction(&tree.app, tree.root), Direction::Ltr);
+        assert_eq!(computed_direction(&tree.app, tree.label), Direction::Ltr);
+        assert_eq!(computed_direction(&tree.app, tree.code), Direction::Ltr);
+    }
+
+    #[test]
+    fn containers_of_every_layout_get_the_direction() {
+        let mut tree = spawn_tree("ar");
+        let world = tree.app.world_mut();
+        let containers = [
+            FlexDirection::Column,
+            FlexDirection::ColumnReverse,
+            FlexDirection::Row,
+        ]
+        .map(|flex_direction| Style {
+            flex_direction,
+            ..default()
+        })
+        .into_iter()
+        .chain([Style {
+            display: Display::Grid,
+            ..default()
+        }])
+        .map(|style| {
+            let child = world.spawn(NodeBundle::default()).id();
+            world
+                .spawn(NodeBundle { style, ..default() })
+                .add_child(child)
+                .id()
+        })
+        .collect::<Vec<_>>();
+        world.entity_mut(tree.root).add_children(&containers);
+        tree.app.update();
+
+        for container in containers {
+            assert_eq!(
+                tree.app.world().get::<Style>(container).unwrap().direction,
+                Direction::Rtl
+            );
+        }
+    }
+
+    #[test]
+    fn changed_nodes_are_updated() {
+        let mut tree = spawn_tree("ar");
+
+        // Spawned into a subtree with an override.
+        let new_code = tree.app.world_mut().spawn(NodeBundle::default()).id();
+        tree.app
+            .world_mut()
+            .entity_mut(tree.code_block)
+            .add_child(new_code);
+        tree.app.update();
+        assert_eq!(computed_direction(&tree.app, new_code), Direction::Ltr);
+
+        // Moved out of it.
+        tree.app
+            .world_mut()
+            .entity_mut(tree.label)
+            .add_child(tree.code);
+        tree.app.update();
+        assert_eq!(computed_direction(&tree.app, tree.code), Direction::Rtl);
+        assert_eq!(
+            tree.app.world().get::<Style>(tree.label).unwrap().direction,
+            Direction::Rtl
+        );
+
+        // Its override removed.
+        tree.app
+            .world_mut()
+            .entity_mut(tree.code_block)
+            .insert(LayoutDirection::Inherit);
+        tree.app.update();
+        assert_eq!(
+            computed_direction(&tree.app, tree.code_block),
+            Direction::Rtl
+        );
+        assert_eq!(computed_direction(&tree.app, new_code), Direction::Rtl);
+    }
+
+    #[test]
+    fn unchanged_subtrees_are_not_walked() {
+        let mut tree = spawn_tree("ar");
+        // A direction that the system would correct if it walked the label.
+        tree.app
+            .world_mut()
+            .get_mut::<ComputedLayoutDirection>(tree.label)
+            .unwrap()
+            .0 = Direction::Ltr;
+
+        let new_code = tree.app.world_mut().spawn(NodeBundle::default()).id();
+        tree.app
+            .world_mut()
+            .entity_mut(tree.code_block)
+            .add_child(new_code);
+        tree.app.update();
+        assert_eq!(computed_direction(&tree.app, new_code), Direction::Ltr);
+        assert_eq!(computed_direction(&tree.app, tree.label), Direction::Ltr);
+    }
+}
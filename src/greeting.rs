@@ -0,0 +1,140 @@
+//! The greeting in the user banner, like "Good evening Sam".
+//!
+//! The message key follows the local clock, and the name comes from the [`UserProfile`], so the
+//! translation decides where the name goes.
+
+use crate::{LocalizedArgs, LocalizedText};
+use bevy::prelude::*;
+use chrono::{Local, Timelike};
+
+/// The user the app greets.
+#[derive(Resource)]
+pub struct UserProfile {
+    pub display_name: String,
+}
+
+impl Default for UserProfile {
+    /// Uses the name of the user account the app runs as.
+    fn default() -> Self {
+        let display_name = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        Self { display_name }
+    }
+}
+
+/// How often greetings follow the clock to the next part of the day.
+const REFRESH_INTERVAL_SECONDS: f32 = 60.;
+
+/// Greets the [`UserProfile`] in the entity's [`LocalizedText`], as `$user`.
+#[derive(Component)]
+pub struct Greeting;
+
+#[derive(Resource)]
+pub struct GreetingTimer(Timer);
+
+impl Default for GreetingTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            REFRESH_INTERVAL_SECONDS,
+            TimerMode::Repeating,
+        ))
+    }
+}
+
+/// Returns the message key of the greeting for `hour`, from 0 to 23, with or without the name
+/// of the user.
+fn greeting_key(hour: u32, named: bool) -> &'static str {
+    match (hour, named) {
+        (5..=11, true) => "greeting_morning",
+        (5..=11, false) => "greeting_morning_anonymous",
+        (12..=17, true) => "greeting_afternoon",
+        (12..=17, false) => "greeting_afternoon_anonymous",
+        (_, true) => "greeting_evening",
+        (_, false) => "greeting_evening_anonymous",
+    }
+}
+
+/// Switches greetings to the current part of the day when they are spawned, and then
+/// periodically, and to the current display name when it changes.
+pub fn update_greetings_system(
+    time: Res<Time>,
+    mut timer: ResMut<GreetingTimer>,
+    user_profile: Res<UserProfile>,
+    mut text_query: Query<(Ref<Greeting>, &mut LocalizedText, &mut LocalizedArgs)>,
+) {
+    let refresh = timer.0.tick(time.delta()).just_finished() || user_profile.is_changed();
+    let name = user_profile.display_name.trim();
+    let key = greeting_key(Local::now().hour(), !name.is_empty());
+    for (greeting, mut localized_text, mut localized_args) in &mut text_query {
+        if !(refresh || greeting.is_added()) {
+            continue;
+        }
+        if localized_text.key != key {
+            localized_text.key = key;
+        }
+        if greeting.is_added() || user_profile.is_changed() {
+            *localized_args = LocalizedArgs::default().with("user", name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_of_the_day() {
+        for (hour, key) in [
+            (0, "greeting_evening"),
+            (4, "greeting_evening"),
+            (5, "greeting_morning"),
+            (11, "greeting_morning"),
+            (12, "greeting_afternoon"),
+            (17, "greeting_afternoon"),
+            (18, "greeting_evening"),
+            (23, "greeting_evening"),
+        ] {
+            assert_eq!(greeting_key(hour, true), key, "{hour}");
+            assert_eq!(
+                greeting_key(hour, false),
+                format!("{key}_anonymous"),
+                "{hour}"
+            );
+        }
+    }
+
+    #[test]
+    fn greets_without_a_name_when_there_is_none() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<GreetingTimer>()
+            .insert_resource(UserProfile {
+                display_name: " ".to_string(),
+            })
+            .add_systems(Update, update_greetings_system);
+        let entity = app
+            .world_mut()
+            .spawn((
+                Greeting,
+                LocalizedText::new("greeting_morning"),
+                LocalizedArgs::default(),
+            ))
+            .id();
+        app.update();
+        let key = |app: &App| app.world().get::<LocalizedText>(entity).unwrap().key;
+        assert!(key(&app).ends_with("_anonymous"));
+
+        app.insert_resource(UserProfile {
+            display_name: "Sam".to_string(),
+        });
+        app.update();
+        assert!(!key(&app).ends_with("_anonymous"));
+        assert!(app
+            .world()
+            .get::<LocalizedArgs>(entity)
+            .unwrap()
+            .0
+            .contains_key("user"));
+    }
+}
@@ -0,0 +1,240 @@
+//! BCP 47 language identifiers, like "ar", "ar-EG" or "pa-Arab-PK".
+//!
+//! Only the language, script and region subtags are supported. Tags with variants or
+//! extensions are rejected.
+
+use bevy::prelude::*;
+use std::{fmt, str::FromStr};
+
+/// Scripts written from right to left, by their ISO 15924 code.
+const RIGHT_TO_LEFT_SCRIPTS: &[&str] = &[
+    "Adlm", "Arab", "Hebr", "Mand", "Nkoo", "Rohg", "Samr", "Syrc", "Thaa",
+];
+
+/// The script a language is written in when its identifier has no script subtag, for the
+/// languages whose script is right-to-left. Other languages are assumed to be left-to-right.
+const LIKELY_SCRIPTS: &[(&str, &str)] = &[
+    ("ar", "Arab"),
+    ("ckb", "Arab"),
+    ("dv", "Thaa"),
+    ("fa", "Arab"),
+    ("he", "Hebr"),
+    ("nqo", "Nkoo"),
+    ("ps", "Arab"),
+    ("sd", "Arab"),
+    ("syr", "Syrc"),
+    ("ug", "Arab"),
+    ("ur", "Arab"),
+    ("yi", "Hebr"),
+];
+
+/// A language, optionally narrowed down to a script and a region, e.g. "ar-EG".
+///
+/// Subtags are stored in their canonical case, so "AR_eg" and "ar-EG" are equal.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LanguageIdentifier {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+}
+
+impl LanguageIdentifier {
+    /// Parses a tag like "ar-EG". Underscores are accepted as separators, as in POSIX locales.
+    pub fn parse(tag: &str) -> Result<Self, ParseLanguageIdentifierError> {
+        let mut subtags = tag.split(['-', '_']).peekable();
+        let invalid = |subtag: &str| ParseLanguageIdentifierError {
+            subtag: subtag.to_string(),
+        };
+
+        let language = subtags.next().unwrap_or_default();
+        if !(matches!(language.len(), 2..=3 | 5..=8)
+            && language.chars().all(|c| c.is_ascii_alphabetic()))
+        {
+            return Err(invalid(language));
+        }
+
+        let script = subtags
+            .next_if(|subtag| subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|subtag| subtag[..1].to_ascii_uppercase() + &subtag[1..].to_ascii_lowercase());
+
+        let region = subtags
+            .next_if(|subtag| {
+                (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+                    || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+            })
+            .map(str::to_ascii_uppercase);
+
+        if let Some(subtag) = subtags.next() {
+            return Err(invalid(subtag));
+        }
+
+        Ok(Self {
+            language: language.to_ascii_lowercase(),
+            script,
+            region,
+        })
+    }
+
+    /// The language subtag, e.g. "ar".
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The script subtag, e.g. "Arab", if the identifier has one.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// The script subtag, or else the script the language is usually written in, if known.
+    pub fn likely_script(&self) -> Option<&str> {
+        self.script().or_else(|| {
+            LIKELY_SCRIPTS
+                .iter()
+                .find(|(language, _)| *language == self.language)
+                .map(|(_, script)| *script)
+        })
+    }
+
+    /// The direction of the script of this language.
+    pub fn direction(&self) -> Direction {
+        match self.likely_script() {
+            Some(script) if RIGHT_TO_LEFT_SCRIPTS.contains(&script) => Direction::Rtl,
+            _ => Direction::Ltr,
+        }
+    }
+
+    /// The identifier without its most specific subtag: "pa-Arab-PK" has the parent "pa-Arab",
+    /// which has the parent "pa", which has none.
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.clone();
+        if parent.region.take().is_none() && parent.script.take().is_none() {
+            return None;
+        }
+        Some(parent)
+    }
+}
+
+impl FromStr for LanguageIdentifier {
+    type Err = ParseLanguageIdentifierError;
+
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        Self::parse(tag)
+    }
+}
+
+impl fmt::Display for LanguageIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+        for subtag in [&self.script, &self.region].into_iter().flatten() {
+            write!(f, "-{subtag}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseLanguageIdentifierError {
+    subtag: String,
+}
+
+impl fmt::Display for ParseLanguageIdentifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid language identifier subtag \"{}\"", self.subtag)
+    }
+}
+
+impl std::error::Error for ParseLanguageIdentifierError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(tag: &str) -> LanguageIdentifier {
+        tag.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_subtags() {
+        let ar_eg = id("ar-EG");
+        assert_eq!(ar_eg.language(), "ar");
+        assert_eq!(ar_eg.script(), None);
+        assert_eq!(ar_eg.region.as_deref(), Some("EG"));
+
+        let pa = id("pa-Arab-PK");
+        assert_eq!(pa.language(), "pa");
+        assert_eq!(pa.script(), Some("Arab"));
+        assert_eq!(pa.region.as_deref(), Some("PK"));
+
+        assert_eq!(id("es-419").region.as_deref(), Some("419"));
+        assert_eq!(id("ckb").language(), "ckb");
+    }
+
+    #[test]
+    fn normalizes_case_and_separators() {
+        assert_eq!(id("AR_eg"), id("ar-EG"));
+        assert_eq!(id("PA-arab-pk"), id("pa-Arab-PK"));
+        assert_eq!(id("sr_latn"), id("sr-Latn"));
+    }
+
+    #[test]
+    fn rejects_invalid_subtags() {
+        for tag in [
+            "",
+            "a",
+            "arabic-language",
+            "ar-",
+            "ar-E",
+            "ar-EGY",
+            "ar-EG-x",
+            "a1",
+            "ar-12",
+        ] {
+            assert!(LanguageIdentifier::parse(tag).is_err(), "{tag:?}");
+        }
+        assert_eq!(
+            LanguageIdentifier::parse("ar-EG-posix")
+                .unwrap_err()
+                .to_string(),
+            "invalid language identifier subtag \"posix\""
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for tag in ["en", "ar-EG", "pa-Arab-PK", "zh-Hant", "es-419"] {
+            assert_eq!(id(tag).to_string(), tag);
+            assert_eq!(id(&id(tag).to_string()), id(tag));
+        }
+        assert_eq!(id("AR_eg").to_string(), "ar-EG");
+    }
+
+    #[test]
+    fn parents() {
+        assert_eq!(id("pa-Arab-PK").parent(), Some(id("pa-Arab")));
+        assert_eq!(id("pa-Arab").parent(), Some(id("pa")));
+        assert_eq!(id("ar-EG").parent(), Some(id("ar")));
+        assert_eq!(id("ar").parent(), None);
+    }
+
+    #[test]
+    fn likely_scripts() {
+        assert_eq!(id("ar-EG").likely_script(), Some("Arab"));
+        assert_eq!(id("he").likely_script(), Some("Hebr"));
+        assert_eq!(id("pa-Arab").likely_script(), Some("Arab"));
+        assert_eq!(id("en").likely_script(), None);
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(id("ar").direction(), Direction::Rtl);
+        assert_eq!(id("ar-EG").direction(), Direction::Rtl);
+        assert_eq!(id("fa-IR").direction(), Direction::Rtl);
+        assert_eq!(id("he").direction(), Direction::Rtl);
+        assert_eq!(id("pa-Arab-PK").direction(), Direction::Rtl);
+        assert_eq!(id("pa").direction(), Direction::Ltr);
+        assert_eq!(id("en").direction(), Direction::Ltr);
+        // An explicit script wins over the usual one.
+        assert_eq!(id("az-Arab").direction(), Direction::Rtl);
+        assert_eq!(id("ar-Latn").direction(), Direction::Ltr);
+    }
+}
@@ -0,0 +1,238 @@
+//! A dropdown that lists every locale of the [`LocaleRegistry`] by its native name and sets
+//! [`CurrentLanguage`] to the chosen one.
+//!
+//! The list opens below the picker, aligned with its inline-end edge, so it stays inside the
+//! window in both directions when the picker sits at the end of a row.
+
+use crate::{
+    language_identifier::LanguageIdentifier, logical::LogicalStyle, CurrentLanguage, LocaleRegistry,
+};
+use bevy::prelude::*;
+
+const IDLE_BACKGROUND: Color = Color::linear_rgb(0.8, 0.8, 0.8);
+const HOVERED_BACKGROUND: Color = Color::linear_rgb(0.6, 0.6, 0.6);
+const SELECTED_BACKGROUND: Color = Color::linear_rgb(0.7, 0.75, 0.9);
+
+/// The button that opens and closes the list.
+#[derive(Component)]
+pub struct LanguagePickerButton;
+
+/// The text of a [`LanguagePickerButton`], showing the native name of the current language.
+#[derive(Component)]
+pub struct LanguagePickerLabel;
+
+/// The list of locales, filled with one [`LanguageOption`] per locale when it is spawned.
+#[derive(Component)]
+pub struct LanguagePickerList {
+    text_style: TextStyle,
+}
+
+/// An entry of a [`LanguagePickerList`].
+#[derive(Component)]
+pub struct LanguageOption(LanguageIdentifier);
+
+/// Spawns a language picker whose texts use `text_style`.
+pub fn language_picker(builder: &mut ChildBuilder, text_style: TextStyle) {
+    builder
+        .spawn(NodeBundle::default())
+        .with_children(|builder| {
+            builder
+                .spawn((
+                    ButtonBundle {
+                        background_color: BackgroundColor(IDLE_BACKGROUND),
+                        border_color: BorderColor(Color::linear_rgb(0.6, 0.6, 0.6)),
+                        ..default()
+                    },
+                    LanguagePickerButton,
+                ))
+                .with_children(|builder| {
+                    builder.spawn((Text::default(), text_style.clone(), LanguagePickerLabel));
+                });
+            builder.spawn((
+                NodeBundle {
+                    style: Style {
+                        display: Display::None,
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(100.),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Stretch,
+                        border: UiRect::all(Val::Px(1.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(IDLE_BACKGROUND),
+                    border_color: BorderColor(Color::BLACK),
+                    ..default()
+                },
+                LogicalStyle {
+                    inset_inline_end: Some(Val::ZERO),
+                    ..default()
+                },
+                // Draw over the content that is spawned after the picker.
+                GlobalZIndex(1),
+                LanguagePickerList { text_style },
+            ));
+        });
+}
+
+/// Adds an option for every locale to new lists.
+pub fn populate_language_picker_system(
+    mut commands: Commands,
+    locale_registry: Res<LocaleRegistry>,
+    list_query: Query<(Entity, &LanguagePickerList), Added<LanguagePickerList>>,
+) {
+    for (entity, list) in &list_query {
+        commands.entity(entity).with_children(|builder| {
+            for locale in locale_registry.iter() {
+                builder
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(10.), Val::Px(2.)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(IDLE_BACKGROUND),
+                            ..default()
+                        },
+                        LanguageOption(locale.id.clone()),
+                    ))
+                    .with_children(|builder| {
+                        builder.spawn((Text::new(locale.native_name), list.text_style.clone()));
+                    });
+            }
+        });
+    }
+}
+
+/// Opens and closes the lists, and switches the language when an option is pressed.
+pub fn language_picker_system(
+    mut current_language: ResMut<CurrentLanguage>,
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (With<LanguagePickerButton>, Changed<Interaction>),
+    >,
+    mut option_query: Query<
+        (&Interaction, &LanguageOption, &mut BackgroundColor),
+        Without<LanguagePickerButton>,
+    >,
+    mut list_query: Query<&mut Style, With<LanguagePickerList>>,
+) {
+    let mut toggle = false;
+    for (interaction, mut background_color) in &mut button_query {
+        match interaction {
+            Interaction::Pressed => toggle = true,
+            Interaction::Hovered => *background_color = BackgroundColor(HOVERED_BACKGROUND),
+            Interaction::None => *background_color = BackgroundColor(IDLE_BACKGROUND),
+        }
+    }
+
+    let mut close = false;
+    for (interaction, option, mut background_color) in &mut option_query {
+        if *interaction == Interaction::Pressed && current_language.0 != option.0 {
+            current_language.0 = option.0.clone();
+        }
+        close |= *interaction == Interaction::Pressed;
+
+        let color = match interaction {
+            Interaction::Hovered | Interaction::Pressed => HOVERED_BACKGROUND,
+            Interaction::None if current_language.0 == option.0 => SELECTED_BACKGROUND,
+            Interaction::None => IDLE_BACKGROUND,
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+
+    if toggle || close {
+        for mut style in &mut list_query {
+            style.display = match style.display {
+                Display::None if !close => Display::Flex,
+                _ => Display::None,
+            };
+        }
+    }
+}
+
+/// Shows the native name of the current language on every picker.
+pub fn update_language_picker_label_system(
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    mut text_query: Query<&mut Text, With<LanguagePickerLabel>>,
+) {
+    let name = locale_registry.get(&current_language.0).map_or_else(
+        || current_language.0.to_string(),
+        |locale| locale.native_name.to_string(),
+    );
+    for mut text in &mut text_query {
+        if text.0 != name {
+            text.0 = name.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::tests::locale_registry;
+
+    fn press(app: &mut App, entity: Entity) {
+        *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::Pressed;
+        app.update();
+        *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::None;
+    }
+
+    fn label(app: &mut App) -> String {
+        app.world_mut()
+            .query_filtered::<&Text, With<LanguagePickerLabel>>()
+            .single(app.world())
+            .0
+            .clone()
+    }
+
+    fn list_display(app: &mut App) -> Display {
+        app.world_mut()
+            .query_filtered::<&Style, With<LanguagePickerList>>()
+            .single(app.world())
+            .display
+    }
+
+    #[test]
+    fn choosing_an_option_switches_the_language() {
+        let mut app = App::new();
+        app.insert_resource(locale_registry())
+            .insert_resource(CurrentLanguage("en".parse().unwrap()))
+            .add_systems(
+                Update,
+                (
+                    populate_language_picker_system,
+                    language_picker_system,
+                    update_language_picker_label_system,
+                )
+                    .chain(),
+            );
+        app.world_mut()
+            .spawn(NodeBundle::default())
+            .with_children(|builder| language_picker(builder, TextStyle::default()));
+        app.update();
+        assert_eq!(label(&mut app), "English");
+        assert_eq!(list_display(&mut app), Display::None);
+
+        let button = app
+            .world_mut()
+            .query_filtered::<Entity, With<LanguagePickerButton>>()
+            .single(app.world());
+        press(&mut app, button);
+        assert_eq!(list_display(&mut app), Display::Flex);
+
+        let ar: LanguageIdentifier = "ar".parse().unwrap();
+        let arabic = app
+            .world_mut()
+            .query::<(Entity, &LanguageOption)>()
+            .iter(app.world())
+            .find_map(|(entity, option)| (option.0 == ar).then_some(entity))
+            .unwrap();
+        press(&mut app, arabic);
+        assert_eq!(app.world().resource::<CurrentLanguage>().0, ar);
+        assert_eq!(label(&mut app), "العربية");
+        assert_eq!(list_display(&mut app), Display::None);
+    }
+}
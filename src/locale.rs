@@ -0,0 +1,284 @@
+use crate::{calendar::CalendarDisplay, language_identifier::LanguageIdentifier};
+use bevy::prelude::*;
+
+/// The digits a locale uses to write numbers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DigitSystem {
+    /// 0123456789
+    Latin,
+    /// ٠١٢٣٤٥٦٧٨٩
+    ArabicIndic,
+    /// ۰۱۲۳۴۵۶۷۸۹, used for Persian and Urdu.
+    ExtendedArabicIndic,
+}
+
+impl DigitSystem {
+    /// Returns the digit system with the given CLDR numbering system identifier, e.g. "arab".
+    pub fn from_cldr_name(name: &str) -> Option<Self> {
+        match name {
+            "latn" => Some(Self::Latin),
+            "arab" => Some(Self::ArabicIndic),
+            "arabext" => Some(Self::ExtendedArabicIndic),
+            _ => None,
+        }
+    }
+
+    fn zero(self) -> char {
+        match self {
+            Self::Latin => '0',
+            Self::ArabicIndic => '\u{0660}',
+            Self::ExtendedArabicIndic => '\u{06F0}',
+        }
+    }
+
+    /// Writes `number` with these digits, without grouping separators.
+    pub fn format(self, number: i64) -> String {
+        self.shape(&number.to_string())
+    }
+
+    /// Replaces the ASCII digits in `text` with these digits, e.g. in "Order #5164134".
+    pub fn shape(self, text: &str) -> String {
+        let zero = self.zero() as u32;
+        text.chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) => char::from_u32(zero + digit).unwrap(),
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// Which digits numbers are written with.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DigitPreference {
+    /// The digits of the current locale.
+    #[default]
+    Locale,
+    /// The given digits, whatever the locale, for users who read e.g. Arabic with Latin digits.
+    Always(DigitSystem),
+}
+
+impl DigitPreference {
+    /// Returns the digits to use for `locale`, or for an unsupported locale if it is `None`.
+    pub fn digits(self, locale: Option<&LocaleInfo>) -> DigitSystem {
+        match self {
+            Self::Locale => locale.map_or(DigitSystem::Latin, |locale| locale.digits),
+            Self::Always(digits) => digits,
+        }
+    }
+}
+
+/// Everything the app needs to know to display a supported locale.
+#[derive(Debug)]
+pub struct LocaleInfo {
+    /// The language of the locale, also the name of its translation file in `assets/locales`.
+    pub id: LanguageIdentifier,
+    /// The name of the locale in its own language, e.g. "العربية".
+    pub native_name: &'static str,
+    /// Overrides the direction of the script of the locale, e.g. for a locale whose script is
+    /// not known to be right-to-left.
+    pub direction: Option<Direction>,
+    /// Asset path of the font used for localized text.
+    pub font: &'static str,
+    pub digits: DigitSystem,
+    pub calendar: CalendarDisplay,
+}
+
+/// The locales the app supports, in the order they are offered to the user.
+#[derive(Resource, Debug)]
+pub struct LocaleRegistry(Vec<LocaleInfo>);
+
+impl LocaleRegistry {
+    pub fn new(locales: Vec<LocaleInfo>) -> Self {
+        assert!(
+            !locales.is_empty(),
+            "LocaleRegistry needs at least one locale"
+        );
+        Self(locales)
+    }
+
+    pub fn get(&self, id: &LanguageIdentifier) -> Option<&LocaleInfo> {
+        self.0.iter().find(|locale| locale.id == *id)
+    }
+
+    /// Returns the text direction of `id`: the direction of its locale if that sets one, or else
+    /// the direction of its script.
+    pub fn direction(&self, id: &LanguageIdentifier) -> Direction {
+        self.get(id)
+            .and_then(|locale| locale.direction)
+            .unwrap_or_else(|| id.direction())
+    }
+
+    /// Returns the supported locale that best matches `requested`: the locale with the same
+    /// identifier, or else the first one with the same language and script, or else the first
+    /// one with the same language.
+    pub fn negotiate(&self, requested: &LanguageIdentifier) -> Option<&LocaleInfo> {
+        let same_language = |locale: &&LocaleInfo| locale.id.language() == requested.language();
+        self.get(requested)
+            .or_else(|| {
+                self.0
+                    .iter()
+                    .filter(same_language)
+                    .find(|locale| locale.id.likely_script() == requested.likely_script())
+            })
+            .or_else(|| self.0.iter().find(same_language))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LocaleInfo> {
+        self.0.iter()
+    }
+}
+
+/// Returns the language the operating system asks for.
+///
+/// Reads the POSIX locale variables in order of precedence: `LC_ALL`, `LC_MESSAGES`, then
+/// `LANG`. The "C" and "POSIX" locales do not ask for any language.
+pub fn os_language() -> Option<LanguageIdentifier> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+    parse_posix_locale(&locale)
+}
+
+/// Parses a POSIX locale name, `language[_territory][.codeset][@modifier]`.
+fn parse_posix_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    match LanguageIdentifier::parse(name) {
+        Ok(id) => Some(id),
+        Err(error) => {
+            warn!("Ignoring the OS locale \"{locale}\": {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// The locales of the app, for tests of systems that depend on the locale.
+    pub fn locale_registry() -> LocaleRegistry {
+        LocaleRegistry::new(vec![
+            LocaleInfo {
+                id: "en".parse().unwrap(),
+                native_name: "English",
+                direction: None,
+                font: "",
+                digits: DigitSystem::Latin,
+                calendar: CalendarDisplay::Gregorian,
+            },
+            LocaleInfo {
+                id: "ar".parse().unwrap(),
+                native_name: "العربية",
+                direction: None,
+                font: "",
+                digits: DigitSystem::ArabicIndic,
+                calendar: CalendarDisplay::Both,
+            },
+        ])
+    }
+
+    #[test]
+    fn direction_comes_from_the_script_unless_overridden() {
+        let mut registry = locale_registry();
+        let direction =
+            |registry: &LocaleRegistry, id: &str| registry.direction(&id.parse().unwrap());
+        assert_eq!(direction(&registry, "en"), Direction::Ltr);
+        assert_eq!(direction(&registry, "ar"), Direction::Rtl);
+        // Unsupported languages still follow their script.
+        assert_eq!(direction(&registry, "he"), Direction::Rtl);
+        assert_eq!(direction(&registry, "fr"), Direction::Ltr);
+
+        registry.0[1].direction = Some(Direction::Ltr);
+        assert_eq!(direction(&registry, "ar"), Direction::Ltr);
+    }
+
+    #[test]
+    fn parses_posix_locales() {
+        let id = |id: &str| Some(id.parse().unwrap());
+        assert_eq!(parse_posix_locale("ar_EG.UTF-8"), id("ar-EG"));
+        assert_eq!(parse_posix_locale("en_GB"), id("en-GB"));
+        assert_eq!(parse_posix_locale("sr_RS@latin"), id("sr-RS"));
+        assert_eq!(parse_posix_locale("ar_EG.UTF-8@modifier"), id("ar-EG"));
+        assert_eq!(parse_posix_locale("C"), None);
+        assert_eq!(parse_posix_locale("C.UTF-8"), None);
+        assert_eq!(parse_posix_locale("POSIX"), None);
+        assert_eq!(parse_posix_locale(""), None);
+        assert_eq!(parse_posix_locale("not a locale"), None);
+    }
+
+    #[test]
+    fn negotiates_the_closest_locale() {
+        let registry = locale_registry();
+        let negotiate = |requested: &str| {
+            registry
+                .negotiate(&requested.parse().unwrap())
+                .map(|locale| locale.id.to_string())
+        };
+        assert_eq!(negotiate("en"), Some("en".to_string()));
+        assert_eq!(negotiate("en-GB"), Some("en".to_string()));
+        assert_eq!(negotiate("ar-EG"), Some("ar".to_string()));
+        assert_eq!(negotiate("ar-Arab-SA"), Some("ar".to_string()));
+        assert_eq!(negotiate("fr"), None);
+    }
+
+    #[test]
+    fn shapes_digits() {
+        assert_eq!(DigitSystem::Latin.format(-5164134), "-5164134");
+        assert_eq!(DigitSystem::ArabicIndic.format(5164134), "٥١٦٤١٣٤");
+        assert_eq!(
+            DigitSystem::ExtendedArabicIndic.format(9876543210),
+            "۹۸۷۶۵۴۳۲۱۰"
+        );
+        assert_eq!(
+            DigitSystem::ArabicIndic.shape("Order receipt #5164134"),
+            "Order receipt #٥١٦٤١٣٤"
+        );
+        assert_eq!(
+            DigitSystem::ExtendedArabicIndic.shape("PR #513, 2 files"),
+            "PR #۵۱۳, ۲ files"
+        );
+        // Digits that are not ASCII are kept as they are.
+        assert_eq!(DigitSystem::Latin.shape("٥ and 5"), "٥ and 5");
+    }
+
+    #[test]
+    fn cldr_names() {
+        assert_eq!(
+            DigitSystem::from_cldr_name("latn"),
+            Some(DigitSystem::Latin)
+        );
+        assert_eq!(
+            DigitSystem::from_cldr_name("arab"),
+            Some(DigitSystem::ArabicIndic)
+        );
+        assert_eq!(
+            DigitSystem::from_cldr_name("arabext"),
+            Some(DigitSystem::ExtendedArabicIndic)
+        );
+        assert_eq!(DigitSystem::from_cldr_name("deva"), None);
+    }
+
+    #[test]
+    fn digit_preference_overrides_the_locale() {
+        let registry = locale_registry();
+        let arabic = registry.get(&"ar".parse().unwrap());
+        assert_eq!(
+            DigitPreference::Locale.digits(arabic),
+            DigitSystem::ArabicIndic
+        );
+        assert_eq!(DigitPreference::Locale.digits(None), DigitSystem::Latin);
+        assert_eq!(
+            DigitPreference::Always(DigitSystem::Latin).digits(arabic),
+            DigitSystem::Latin
+        );
+        assert_eq!(
+            DigitPreference::Always(DigitSystem::ExtendedArabicIndic).digits(None),
+            DigitSystem::ExtendedArabicIndic
+        );
+    }
+}
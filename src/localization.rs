@@ -0,0 +1,803 @@
+use crate::{
+    language_identifier::{LanguageIdentifier, ParseLanguageIdentifierError},
+    locale::{DigitSystem, LocaleRegistry},
+    plural::{plural_category, PluralCategory},
+    LocalizationDatabase,
+};
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    utils::HashMap,
+};
+use std::fmt;
+
+/// Loads the translation files and keeps [`LocalizationDatabase`] in sync with them.
+///
+/// Translation files are hot-reloaded: editing one while the app is running updates every
+/// [`LocalizedText`](crate::LocalizedText) through `change_language_system`.
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Translations>()
+            .init_asset_loader::<TranslationsLoader>()
+            .init_resource::<LocalizationDatabase>()
+            .init_resource::<TranslationHandles>()
+            .init_state::<TranslationsState>()
+            .add_systems(Startup, load_translations)
+            .add_systems(
+                Update,
+                (
+                    update_localization_database,
+                    finish_loading_translations
+                        .after(update_localization_database)
+                        .run_if(in_state(TranslationsState::Loading)),
+                ),
+            );
+    }
+}
+
+/// Whether the translation files of every locale have been loaded.
+///
+/// Localized texts should be spawned once the state is [`TranslationsState::Loaded`], so their
+/// first frame shows the translated message instead of its key.
+#[derive(States, Default, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TranslationsState {
+    #[default]
+    Loading,
+    Loaded,
+}
+
+/// The messages of a single translation file.
+///
+/// The language is taken from the file name, so `locales/ar.ftl` holds the "ar" messages.
+#[derive(Asset, TypePath, Debug)]
+pub struct Translations {
+    pub language: LanguageIdentifier,
+    pub messages: HashMap<String, Pattern>,
+}
+
+/// Keeps the translation handles alive for the lifetime of the app.
+#[derive(Resource, Default)]
+struct TranslationHandles(Vec<Handle<Translations>>);
+
+/// Loads `assets/locales/<language>.ftl` for every locale in the [`LocaleRegistry`].
+fn load_translations(
+    asset_server: Res<AssetServer>,
+    locale_registry: Res<LocaleRegistry>,
+    mut handles: ResMut<TranslationHandles>,
+) {
+    for locale in locale_registry.iter() {
+        handles
+            .0
+            .push(asset_server.load(format!("locales/{}.ftl", locale.id)));
+    }
+}
+
+/// Moves to [`TranslationsState::Loaded`] once every translation file is in the
+/// [`LocalizationDatabase`] or has failed to load.
+fn finish_loading_translations(
+    asset_server: Res<AssetServer>,
+    handles: Res<TranslationHandles>,
+    translations: Res<Assets<Translations>>,
+    localization_database: Res<LocalizationDatabase>,
+    mut next_state: ResMut<NextState<TranslationsState>>,
+) {
+    let finished = handles.0.iter().all(|handle| {
+        asset_server.load_state(handle).is_failed()
+            || translations.get(handle).is_some_and(|translations| {
+                localization_database.has_language(&translations.language)
+            })
+    });
+    if finished {
+        next_state.set(TranslationsState::Loaded);
+    }
+}
+
+fn update_localization_database(
+    mut events: EventReader<AssetEvent<Translations>>,
+    translations: Res<Assets<Translations>>,
+    mut localization_database: ResMut<LocalizationDatabase>,
+) {
+    for event in events.read() {
+        // `Modified` is sent when the file watcher reloads a translation file, which lets
+        // translators check their wording without restarting the app.
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(translations) = translations.get(*id) else {
+            continue;
+        };
+        if matches!(event, AssetEvent::Modified { .. }) {
+            info!("Reloaded \"{}\" translations", translations.language);
+        }
+        localization_database
+            .0
+            .insert(translations.language.clone(), translations.messages.clone());
+    }
+}
+
+#[derive(Default)]
+struct TranslationsLoader;
+
+impl AssetLoader for TranslationsLoader {
+    type Asset = Translations;
+    type Settings = ();
+    type Error = TranslationsLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Translations, TranslationsLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes)?;
+        let language = load_context
+            .path()
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or(TranslationsLoaderError::MissingLanguage)?
+            .parse::<LanguageIdentifier>()?;
+
+        Ok(Translations {
+            language,
+            messages: parse_messages(&source)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+#[derive(Debug)]
+pub enum TranslationsLoaderError {
+    Io(std::io::Error),
+    Utf8(std::string::FromUtf8Error),
+    Parse(ParseError),
+    MissingLanguage,
+    InvalidLanguage(ParseLanguageIdentifierError),
+}
+
+impl fmt::Display for TranslationsLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read translation file: {error}"),
+            Self::Utf8(error) => write!(f, "translation file is not valid UTF-8: {error}"),
+            Self::Parse(error) => write!(f, "could not parse translation file: {error}"),
+            Self::MissingLanguage => write!(f, "translation file name does not name a language"),
+            Self::InvalidLanguage(error) => {
+                write!(f, "translation file name is not a language: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranslationsLoaderError {}
+
+impl From<ParseLanguageIdentifierError> for TranslationsLoaderError {
+    fn from(error: ParseLanguageIdentifierError) -> Self {
+        Self::InvalidLanguage(error)
+    }
+}
+
+impl From<std::io::Error> for TranslationsLoaderError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for TranslationsLoaderError {
+    fn from(error: std::string::FromUtf8Error) -> Self {
+        Self::Utf8(error)
+    }
+}
+
+impl From<ParseError> for TranslationsLoaderError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parses the subset of the Fluent syntax used by our translation files:
+///
+/// ```ftl
+/// # Comments start with a hash.
+/// simple_key = A message on one line
+/// multiline_key =
+///     Indented lines continue
+///     the previous message.
+/// select_key = { $count ->
+///    *[other] Lines starting with `[`, `*[` or `}` continue it too.
+/// }
+/// ```
+///
+/// Leading and trailing whitespace of every line is trimmed, as in Fluent.
+/// Message values are parsed with [`Pattern::parse`].
+pub fn parse_messages(source: &str) -> Result<HashMap<String, Pattern>, ParseError> {
+    let mut messages = HashMap::new();
+    // Key, value and line number of the message being parsed.
+    let mut current: Option<(String, String, usize)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+
+        // Fluent only allows these characters at the start of a line inside a message.
+        let is_continuation = line.starts_with(char::is_whitespace)
+            || line.starts_with(['[', '}'])
+            || line.starts_with("*[");
+        if is_continuation && !trimmed.is_empty() {
+            let Some((_, value, _)) = &mut current else {
+                return Err(ParseError {
+                    line: line_number,
+                    message: "continuation line outside of a message",
+                });
+            };
+            if !value.is_empty() {
+                value.push('\n');
+            }
+            value.push_str(trimmed);
+            continue;
+        }
+
+        if let Some((key, value, line)) = current.take() {
+            insert_message(&mut messages, key, value, line)?;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once('=') else {
+            return Err(ParseError {
+                line: line_number,
+                message: "expected `key = value`",
+            });
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ParseError {
+                line: line_number,
+                message: "invalid message key",
+            });
+        }
+        current = Some((key.to_string(), value.trim().to_string(), line_number));
+    }
+
+    if let Some((key, value, line)) = current.take() {
+        insert_message(&mut messages, key, value, line)?;
+    }
+
+    Ok(messages)
+}
+
+fn insert_message(
+    messages: &mut HashMap<String, Pattern>,
+    key: String,
+    value: String,
+    line: usize,
+) -> Result<(), ParseError> {
+    if value.is_empty() {
+        return Err(ParseError {
+            line,
+            message: "message has no value",
+        });
+    }
+    let pattern = Pattern::parse(&value).map_err(|message| ParseError { line, message })?;
+    if messages.insert(key, pattern).is_some() {
+        return Err(ParseError {
+            line,
+            message: "duplicate message key",
+        });
+    }
+    Ok(())
+}
+
+/// A value that can be referenced from a message as a variable, e.g. `{ $count }`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ArgValue {
+    Number(i64),
+    String(String),
+}
+
+impl From<i64> for ArgValue {
+    fn from(number: i64) -> Self {
+        Self::Number(number)
+    }
+}
+
+impl From<String> for ArgValue {
+    fn from(text: String) -> Self {
+        Self::String(text)
+    }
+}
+
+impl From<&str> for ArgValue {
+    fn from(text: &str) -> Self {
+        Self::String(text.to_string())
+    }
+}
+
+/// A parsed message value: text mixed with `{ ... }` placeables.
+#[derive(Clone, Debug)]
+pub struct Pattern(Vec<PatternElement>);
+
+#[derive(Clone, Debug)]
+enum PatternElement {
+    Text(String),
+    Placeable(Expression),
+}
+
+#[derive(Clone, Debug)]
+enum Expression {
+    StringLiteral(String),
+    NumberLiteral(i64),
+    Variable(String),
+    Select {
+        selector: String,
+        variants: Vec<Variant>,
+        default: usize,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Variant {
+    key: VariantKey,
+    value: Pattern,
+}
+
+#[derive(Clone, Debug)]
+enum VariantKey {
+    Number(i64),
+    Identifier(String),
+}
+
+impl Pattern {
+    /// Parses a message value. Besides plain text, the following placeables are supported:
+    ///
+    /// ```ftl
+    /// string_literal = Good morning{" "}
+    /// variable = { $count } messages
+    /// plural = { $count ->
+    ///     [0] No messages
+    ///     [one] One message
+    ///    *[other] { $count } messages
+    /// }
+    /// ```
+    ///
+    /// Select expressions match numeric variables against exact numbers first and then against
+    /// the CLDR plural category of the number, see [`plural_category`]. String variables match
+    /// identifier keys. Each variant value must fit on one line.
+    pub fn parse(source: &str) -> Result<Self, &'static str> {
+        let mut parser = PatternParser { rest: source };
+        let pattern = parser.pattern(false)?;
+        if !parser.rest.is_empty() {
+            return Err("unexpected `}`");
+        }
+        Ok(pattern)
+    }
+
+    /// Formats the pattern in `language`, looking up variables in `args` and writing numbers
+    /// with `digits`.
+    ///
+    /// Variables missing from `args` are rendered as `{$name}`, as Fluent does.
+    pub fn format(
+        &self,
+        language: &str,
+        digits: DigitSystem,
+        args: &HashMap<&str, ArgValue>,
+    ) -> String {
+        let mut output = String::new();
+        self.write(language, digits, args, &mut output);
+        output
+    }
+
+    fn write(
+        &self,
+        language: &str,
+        digits: DigitSystem,
+        args: &HashMap<&str, ArgValue>,
+        output: &mut String,
+    ) {
+        for element in &self.0 {
+            match element {
+                PatternElement::Text(text) => output.push_str(text),
+                PatternElement::Placeable(expression) => {
+                    expression.write(language, digits, args, output);
+                }
+            }
+        }
+    }
+}
+
+impl Expression {
+    fn write(
+        &self,
+        language: &str,
+        digits: DigitSystem,
+        args: &HashMap<&str, ArgValue>,
+        output: &mut String,
+    ) {
+        match self {
+            Expression::StringLiteral(text) => output.push_str(text),
+            Expression::NumberLiteral(number) => output.push_str(&digits.format(*number)),
+            Expression::Variable(name) => match args.get(name.as_str()) {
+                Some(ArgValue::Number(number)) => output.push_str(&digits.format(*number)),
+                Some(ArgValue::String(text)) => output.push_str(text),
+                None => {
+                    output.push_str("{$");
+                    output.push_str(name);
+                    output.push('}');
+                }
+            },
+            Expression::Select {
+                selector,
+                variants,
+                default,
+            } => {
+                let selected = args
+                    .get(selector.as_str())
+                    .and_then(|value| select_variant(variants, value, language));
+                variants[selected.unwrap_or(*default)]
+                    .value
+                    .write(language, digits, args, output);
+            }
+        }
+    }
+}
+
+/// Picks the variant whose key matches `value`, see [`Pattern::parse`].
+fn select_variant(variants: &[Variant], value: &ArgValue, language: &str) -> Option<usize> {
+    match value {
+        ArgValue::Number(number) => variants
+            .iter()
+            .position(|variant| matches!(variant.key, VariantKey::Number(key) if key == *number))
+            .or_else(|| {
+                let category = plural_category(language, *number);
+                variants.iter().position(|variant| match &variant.key {
+                    VariantKey::Identifier(key) => PluralCategory::from_name(key) == Some(category),
+                    VariantKey::Number(_) => false,
+                })
+            }),
+        ArgValue::String(text) => variants.iter().position(|variant| match &variant.key {
+            VariantKey::Identifier(key) => key == text,
+            VariantKey::Number(_) => false,
+        }),
+    }
+}
+
+struct PatternParser<'a> {
+    rest: &'a str,
+}
+
+impl PatternParser<'_> {
+    /// Parses text and placeables. A variant value stops at the end of its line or at the
+    /// `}` that closes its select expression.
+    fn pattern(&mut self, in_variant: bool) -> Result<Pattern, &'static str> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.rest.chars().next() {
+            match c {
+                '{' => {
+                    self.rest = &self.rest[1..];
+                    if !text.is_empty() {
+                        elements.push(PatternElement::Text(std::mem::take(&mut text)));
+                    }
+                    elements.push(PatternElement::Placeable(self.placeable()?));
+                }
+                '}' => break,
+                '\n' if in_variant => break,
+                _ => {
+                    text.push(c);
+                    self.rest = &self.rest[c.len_utf8()..];
+                }
+            }
+        }
+
+        if in_variant {
+            text.truncate(text.trim_end().len());
+        }
+        if !text.is_empty() {
+            elements.push(PatternElement::Text(text));
+        }
+        Ok(Pattern(elements))
+    }
+
+    /// Parses the inside of a placeable, after its opening `{`.
+    fn placeable(&mut self) -> Result<Expression, &'static str> {
+        self.skip_whitespace();
+        let expression = self.inline_expression()?;
+        self.skip_whitespace();
+
+        let expression = if let Some(rest) = self.rest.strip_prefix("->") {
+            self.rest = rest;
+            let Expression::Variable(selector) = expression else {
+                return Err("only variables can be used as selectors");
+            };
+            self.select(selector)?
+        } else {
+            expression
+        };
+
+        self.skip_whitespace();
+        self.rest = self.rest.strip_prefix('}').ok_or("expected `}`")?;
+        Ok(expression)
+    }
+
+    fn inline_expression(&mut self) -> Result<Expression, &'static str> {
+        if let Some(rest) = self.rest.strip_prefix('$') {
+            self.rest = rest;
+            return Ok(Expression::Variable(self.identifier()?.to_string()));
+        }
+
+        if let Some(rest) = self.rest.strip_prefix('"') {
+            let mut literal = String::new();
+            let mut chars = rest.char_indices();
+            while let Some((index, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.rest = &rest[index + 1..];
+                        return Ok(Expression::StringLiteral(literal));
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, escaped @ ('"' | '\\'))) => literal.push(escaped),
+                        _ => return Err("unknown escape sequence in string literal"),
+                    },
+                    '\n' => break,
+                    _ => literal.push(c),
+                }
+            }
+            return Err("unterminated string literal");
+        }
+
+        let end = self
+            .rest
+            .char_indices()
+            .find(|&(index, c)| !(c.is_ascii_digit() || (index == 0 && c == '-')))
+            .map_or(self.rest.len(), |(index, _)| index);
+        let number = self.rest[..end]
+            .parse()
+            .map_err(|_| "expected a variable, string or number")?;
+        self.rest = &self.rest[end..];
+        Ok(Expression::NumberLiteral(number))
+    }
+
+    /// Parses the variants of a select expression, after its `->`.
+    fn select(&mut self, selector: String) -> Result<Expression, &'static str> {
+        let mut variants = Vec::new();
+        let mut default = None;
+
+        loop {
+            self.skip_whitespace();
+            let is_default = if let Some(rest) = self.rest.strip_prefix('*') {
+                self.rest = rest;
+                true
+            } else {
+                false
+            };
+            let Some(rest) = self.rest.strip_prefix('[') else {
+                break;
+            };
+            let (key, rest) = rest.split_once(']').ok_or("expected `]`")?;
+            self.rest = rest.trim_start_matches([' ', '\t']);
+
+            let key = key.trim();
+            let key = match key.parse() {
+                Ok(number) => VariantKey::Number(number),
+                Err(_) if is_identifier(key) => VariantKey::Identifier(key.to_string()),
+                Err(_) => return Err("invalid variant key"),
+            };
+            if is_default {
+                if default.is_some() {
+                    return Err("select expression has more than one default variant");
+                }
+                default = Some(variants.len());
+            }
+            variants.push(Variant {
+                key,
+                value: self.pattern(true)?,
+            });
+        }
+
+        Ok(Expression::Select {
+            selector,
+            variants,
+            default: default.ok_or("select expression needs a `*[default]` variant")?,
+        })
+    }
+
+    fn identifier(&mut self) -> Result<&str, &'static str> {
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(self.rest.len());
+        let (identifier, rest) = self.rest.split_at(end);
+        if !is_identifier(identifier) {
+            return Err("expected an identifier");
+        }
+        self.rest = rest;
+        Ok(identifier)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(pattern: &Pattern, language: &str, args: &[(&'static str, ArgValue)]) -> String {
+        let args = args.iter().cloned().collect();
+        pattern.format(language, DigitSystem::Latin, &args)
+    }
+
+    fn format_message(
+        source: &str,
+        key: &str,
+        language: &str,
+        digits: DigitSystem,
+        count: i64,
+    ) -> String {
+        let messages = parse_messages(source).unwrap();
+        let args = [("count", ArgValue::Number(count))].into_iter().collect();
+        messages[key].format(language, digits, &args)
+    }
+
+    #[test]
+    fn parses_single_and_multiline_messages() {
+        let messages = parse_messages(
+            "# A comment\n\
+             simple = One line\n\
+             \n\
+             multiline =\n    First line\n    second line\n",
+        )
+        .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(format(&messages["simple"], "en", &[]), "One line");
+        assert_eq!(
+            format(&messages["multiline"], "en", &[]),
+            "First line\nsecond line"
+        );
+    }
+
+    #[test]
+    fn parses_select_lines_at_column_zero() {
+        let messages = parse_messages("a = { $x ->\n *[other] b\n}\nc = d\n").unwrap();
+        assert_eq!(format(&messages["a"], "en", &[]), "b");
+        assert_eq!(format(&messages["c"], "en", &[]), "d");
+
+        let messages = parse_messages("a = { $x ->\n[one] b\n*[other] c\n}\n").unwrap();
+        assert_eq!(format(&messages["a"], "en", &[("x", 1.into())]), "b");
+        assert_eq!(format(&messages["a"], "en", &[("x", 2.into())]), "c");
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let line = |source| parse_messages(source).unwrap_err().line;
+        assert_eq!(line("    indented = before any message\n"), 1);
+        assert_eq!(line("a = b\nno equals sign\n"), 2);
+        assert_eq!(line("a = b\na = c\n"), 2);
+        assert_eq!(line("a = b\nempty =\n"), 2);
+        assert_eq!(line("a = { $x ->\n    [one] b\n}\n"), 1);
+    }
+
+    #[test]
+    fn formats_placeables() {
+        let pattern = Pattern::parse(r#"Hello{" "}{ $user }, { 42 } { $missing }"#).unwrap();
+        assert_eq!(
+            format(&pattern, "en", &[("user", "Sam".into())]),
+            "Hello Sam, 42 {$missing}"
+        );
+
+        let pattern = Pattern::parse("{ $count } / { 12 }").unwrap();
+        let args = [("count", ArgValue::Number(305))].into_iter().collect();
+        assert_eq!(
+            pattern.format("ar", DigitSystem::ArabicIndic, &args),
+            "٣٠٥ / ١٢"
+        );
+    }
+
+    #[test]
+    fn selects_exact_numbers_before_plural_categories() {
+        let pattern =
+            Pattern::parse("{ $count ->\n[0] none\n[one] one\n*[other] { $count } items\n}")
+                .unwrap();
+        assert_eq!(format(&pattern, "en", &[("count", 0.into())]), "none");
+        assert_eq!(format(&pattern, "en", &[("count", 1.into())]), "one");
+        assert_eq!(format(&pattern, "en", &[("count", 7.into())]), "7 items");
+        // A missing selector uses the default variant.
+        assert_eq!(format(&pattern, "en", &[]), "{$count} items");
+    }
+
+    #[test]
+    fn selects_string_variants() {
+        let pattern = Pattern::parse("{ $kind ->\n[inbox] Inbox\n*[other] Folder\n}").unwrap();
+        assert_eq!(format(&pattern, "en", &[("kind", "inbox".into())]), "Inbox");
+        assert_eq!(format(&pattern, "en", &[("kind", "sent".into())]), "Folder");
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(Pattern::parse("{ $count").is_err());
+        assert!(Pattern::parse("text }").is_err());
+        assert!(Pattern::parse("{ $count ->\n[one] one\n}").is_err());
+        assert!(Pattern::parse("{ $count ->\n*[one] one\n*[other] other\n}").is_err());
+        assert!(Pattern::parse("{ \"unterminated }").is_err());
+    }
+
+    #[test]
+    fn shipped_translations_parse() {
+        let english = parse_messages(include_str!("../assets/locales/en.ftl")).unwrap();
+        let arabic = parse_messages(include_str!("../assets/locales/ar.ftl")).unwrap();
+        let mut english_keys: Vec<_> = english.keys().collect();
+        let mut arabic_keys: Vec<_> = arabic.keys().collect();
+        english_keys.sort();
+        arabic_keys.sort();
+        assert_eq!(english_keys, arabic_keys);
+        // Saudi Arabic only overrides the locale settings, its messages are all Arabic.
+        assert!(parse_messages(include_str!("../assets/locales/ar-SA.ftl"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn arabic_unread_messages_use_every_plural_form() {
+        let source = include_str!("../assets/locales/ar.ftl");
+        let unread = |count| {
+            format_message(
+                source,
+                "unread_messages",
+                "ar",
+                DigitSystem::ArabicIndic,
+                count,
+            )
+        };
+        assert_eq!(unread(0), "لا توجد رسائل غير مقروءة");
+        assert_eq!(unread(1), "رسالة واحدة غير مقروءة");
+        assert_eq!(unread(2), "رسالتان غير مقروءتين");
+        assert_eq!(unread(3), "٣ رسائل غير مقروءة");
+        assert_eq!(unread(11), "١١ رسالة غير مقروءة");
+        assert_eq!(unread(100), "١٠٠ رسالة غير مقروءة");
+        assert_eq!(unread(102), "١٠٢ رسالة غير مقروءة");
+    }
+
+    #[test]
+    fn english_unread_messages_prefer_the_exact_zero() {
+        let source = include_str!("../assets/locales/en.ftl");
+        let unread =
+            |count| format_message(source, "unread_messages", "en", DigitSystem::Latin, count);
+        assert_eq!(unread(0), "No unread messages");
+        assert_eq!(unread(1), "1 unread message");
+        assert_eq!(unread(2), "2 unread messages");
+    }
+}
@@ -0,0 +1,572 @@
+//! Logical layout properties, which refer to the start and end of a node's inline direction
+//! instead of its left and right, so that layouts mirror in right-to-left locales.
+//!
+//! The block axis is always vertical: block start is the top and block end is the bottom.
+
+use crate::direction::ComputedLayoutDirection;
+use bevy::prelude::*;
+
+/// Like [`UiRect`], but with inline start and end edges instead of left and right.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LogicalRect {
+    pub inline_start: Val,
+    pub inline_end: Val,
+    pub block_start: Val,
+    pub block_end: Val,
+}
+
+impl LogicalRect {
+    pub const DEFAULT: Self = Self::all(Val::ZERO);
+
+    pub const fn all(value: Val) -> Self {
+        Self {
+            inline_start: value,
+            inline_end: value,
+            block_start: value,
+            block_end: value,
+        }
+    }
+
+    /// Returns the physical rectangle for a node laid out in `direction`.
+    pub fn to_physical(self, direction: Direction) -> UiRect {
+        let (left, right) = if direction == Direction::Rtl {
+            (self.inline_end, self.inline_start)
+        } else {
+            (self.inline_start, self.inline_end)
+        };
+        UiRect {
+            left,
+            right,
+            top: self.block_start,
+            bottom: self.block_end,
+        }
+    }
+}
+
+impl Default for LogicalRect {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Logical replacements for [`Style`] properties.
+///
+/// Every property that is set overwrites its physical counterpart in the node's `Style`
+/// whenever the node's [`ComputedLayoutDirection`] changes.
+#[derive(Component, Clone, Debug, Default)]
+pub struct LogicalStyle {
+    pub padding: Option<LogicalRect>,
+    pub margin: Option<LogicalRect>,
+    pub border: Option<LogicalRect>,
+    /// Offset of an absolutely positioned node from the inline start of its container.
+    pub inset_inline_start: Option<Val>,
+    /// Offset of an absolutely positioned node from the inline end of its container.
+    pub inset_inline_end: Option<Val>,
+    /// The grid columns of the node, with lines counted from the inline start of the grid.
+    pub grid_column: Option<GridPlacement>,
+}
+
+impl LogicalStyle {
+    /// Returns the physical `(left, right)` insets for a node laid out in `direction`, or `None`
+    /// if neither logical inset is set. An unset side resolves to [`Val::Auto`], so that the
+    /// previous side is cleared when the direction flips.
+    fn physical_inset(&self, direction: Direction) -> Option<(Val, Val)> {
+        if self.inset_inline_start.is_none() && self.inset_inline_end.is_none() {
+            return None;
+        }
+        let start = self.inset_inline_start.unwrap_or(Val::Auto);
+        let end = self.inset_inline_end.unwrap_or(Val::Auto);
+        Some(if direction == Direction::Rtl {
+            (end, start)
+        } else {
+            (start, end)
+        })
+    }
+}
+
+/// Like [`BorderRadius`], but with corners named after their block and inline edges, e.g.
+/// `start_end` is the corner between the block start and the inline end.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct LogicalBorderRadius {
+    pub start_start: Val,
+    pub start_end: Val,
+    pub end_start: Val,
+    pub end_end: Val,
+}
+
+impl LogicalBorderRadius {
+    pub const DEFAULT: Self = Self::all(Val::ZERO);
+
+    pub const fn all(radius: Val) -> Self {
+        Self {
+            start_start: radius,
+            start_end: radius,
+            end_start: radius,
+            end_end: radius,
+        }
+    }
+
+    /// Returns the physical radii for a node laid out in `direction`.
+    pub fn to_physical(self, direction: Direction) -> BorderRadius {
+        let (top_left, top_right, bottom_left, bottom_right) = if direction == Direction::Rtl {
+            (
+                self.start_end,
+                self.start_start,
+                self.end_end,
+                self.end_start,
+            )
+        } else {
+            (
+                self.start_start,
+                self.start_end,
+                self.end_start,
+                self.end_end,
+            )
+        };
+        BorderRadius {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+impl Default for LogicalBorderRadius {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Places a grid item in the last column of the explicit grid.
+pub fn last_column() -> GridPlacement {
+    GridPlacement::start_end(-2, -1)
+}
+
+/// Places a grid item from line `start` to the end of the explicit grid.
+pub fn span_to_end(start: i16) -> GridPlacement {
+    GridPlacement::start_end(start, -1)
+}
+
+/// Returns the physical placement of a grid item whose lines count from the inline start.
+///
+/// The layout engine always counts grid lines from the left, so right-to-left placements are
+/// mirrored: line `n` becomes line `-n`, which counts from the right. Negative lines only
+/// address the explicit grid, and automatically placed items are left as they are.
+pub fn grid_placement_to_physical(placement: GridPlacement, direction: Direction) -> GridPlacement {
+    if direction != Direction::Rtl {
+        return placement;
+    }
+    match (placement.get_start(), placement.get_end()) {
+        (Some(start), Some(end)) => GridPlacement::start_end(-end, -start),
+        (Some(start), None) => GridPlacement::end_span(-start, placement.get_span().unwrap_or(1)),
+        (None, Some(end)) => GridPlacement::start_span(-end, placement.get_span().unwrap_or(1)),
+        (None, None) => placement,
+    }
+}
+
+pub fn resolve_logical_style_system(
+    mut style_query: Query<
+        (&LogicalStyle, &ComputedLayoutDirection, &mut Style),
+        Or<(Changed<LogicalStyle>, Changed<ComputedLayoutDirection>)>,
+    >,
+) {
+    for (logical_style, direction, mut style) in &mut style_query {
+        // Only mark the style as changed when a value actually changes, to avoid re-layouts.
+        let physical_style = style.bypass_change_detection();
+        let mut changed = false;
+
+        for (logical, physical) in [
+            (logical_style.padding, &mut physical_style.padding),
+            (logical_style.margin, &mut physical_style.margin),
+            (logical_style.border, &mut physical_style.border),
+        ] {
+            if let Some(logical) = logical {
+                let resolved = logical.to_physical(direction.0);
+                if *physical != resolved {
+                    *physical = resolved;
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some((left, right)) = logical_style.physical_inset(direction.0) {
+            if physical_style.left != left || physical_style.right != right {
+                physical_style.left = left;
+                physical_style.right = right;
+                changed = true;
+            }
+        }
+
+        if let Some(grid_column) = logical_style.grid_column {
+            let resolved = grid_placement_to_physical(grid_column, direction.0);
+            if physical_style.grid_column != resolved {
+                physical_style.grid_column = resolved;
+                changed = true;
+            }
+        }
+
+        if changed {
+            style.set_changed();
+        }
+    }
+}
+
+pub fn resolve_logical_border_radius_system(
+    mut border_radius_query: Query<
+        (
+            &LogicalBorderRadius,
+            &ComputedLayoutDirection,
+            &mut BorderRadius,
+        ),
+        Or<(
+            Changed<LogicalBorderRadius>,
+            Changed<ComputedLayoutDirection>,
+        )>,
+    >,
+) {
+    for (logical_border_radius, direction, mut border_radius) in &mut border_radius_query {
+        let resolved = logical_border_radius.to_physical(direction.0);
+        if *border_radius != resolved {
+            *border_radius = resolved;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        direction::propagate_layout_direction_system, locale::tests::locale_registry, mail_ui,
+        test_ui, CurrentLanguage, UiAssets,
+    };
+    use bevy::{
+        render::{settings::WgpuSettings, RenderPlugin},
+        ui::UiSystem,
+        window::WindowResolution,
+        winit::WinitPlugin,
+    };
+
+    const WINDOW_WIDTH: f32 = 1000.;
+
+    /// Returns the first and last track, counted from the left, that `placement` covers in a
+    /// grid of `track_count` explicit columns.
+    fn physical_tracks(placement: GridPlacement, track_count: i16) -> (i16, i16) {
+        let line = |line: i16| {
+            if line > 0 {
+                line
+            } else {
+                track_count + 2 + line
+            }
+        };
+        let span = placement.get_span().unwrap_or(1) as i16;
+        match (placement.get_start(), placement.get_end()) {
+            (Some(start), Some(end)) => (line(start), line(end) - 1),
+            (Some(start), None) => (line(start), line(start) + span - 1),
+            (None, Some(end)) => (line(end) - span, line(end) - 1),
+            (None, None) => panic!("{placement:?} is placed automatically"),
+        }
+    }
+
+    /// Spawns a demo layout in `language` and returns its resolved `Style`s by name.
+    fn resolve_demo(
+        language: &str,
+        demo: fn(&mut ChildBuilder, &UiAssets),
+    ) -> Vec<(String, Style)> {
+        let mut app = App::new();
+        app.insert_resource(locale_registry())
+            .insert_resource(CurrentLanguage(language.parse().unwrap()))
+            .add_systems(Startup, move |mut commands: Commands| {
+                commands
+                    .spawn(NodeBundle::default())
+                    .with_children(|builder| demo(builder, &UiAssets::default()));
+            })
+            .add_systems(
+                Update,
+                (
+                    propagate_layout_direction_system,
+                    resolve_logical_style_system,
+                )
+                    .chain(),
+            );
+        app.update();
+
+        let mut query = app
+            .world_mut()
+            .query::<(&Style, Option<&Name>, Option<&Text>)>();
+        query
+            .iter(app.world())
+            .filter_map(|(style, name, text)| {
+                let name = name
+                    .map(|name| name.to_string())
+                    .or(text.map(|text| text.0.clone()))?;
+                Some((name, style.clone()))
+            })
+            .collect()
+    }
+
+    /// Lays out a demo layout in `language` in a window that is [`WINDOW_WIDTH`] wide, with the
+    /// layout engine itself, and returns the horizontal center of its nodes by name.
+    fn layout_demo(language: &str, demo: fn(&mut ChildBuilder, &UiAssets)) -> Vec<(String, f32)> {
+        let mut app = App::new();
+        app.add_plugins(
+            DefaultPlugins
+                .build()
+                .disable::<WinitPlugin>()
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        resolution: WindowResolution::new(WINDOW_WIDTH, 600.),
+                        ..default()
+                    }),
+                    ..default()
+                }),
+        )
+        .insert_resource(locale_registry())
+        .insert_resource(CurrentLanguage(language.parse().unwrap()))
+        .add_systems(Startup, move |mut commands: Commands| {
+            commands.spawn(Camera2d);
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| demo(builder, &UiAssets::default()));
+        })
+        .add_systems(
+            PostUpdate,
+            (
+                propagate_layout_direction_system,
+                resolve_logical_style_system,
+            )
+                .chain()
+                .before(UiSystem::Prepare),
+        );
+        app.finish();
+        app.cleanup();
+        // The camera finds its window on the first update.
+        app.update();
+        app.update();
+
+        let mut query = app
+            .world_mut()
+            .query::<(&GlobalTransform, Option<&Name>, Option<&Text>)>();
+        query
+            .iter(app.world())
+            .filter_map(|(transform, name, text)| {
+                let name = name
+                    .map(|name| name.to_string())
+                    .or(text.map(|text| text.0.clone()))?;
+                Some((name, transform.translation().x))
+            })
+            .collect()
+    }
+
+    fn center_x(centers: &[(String, f32)], name: &str) -> f32 {
+        centers
+            .iter()
+            .find(|(center_name, _)| center_name == name)
+            .unwrap_or_else(|| panic!("no node named {name:?}"))
+            .1
+    }
+
+    fn grid_column(styles: &[(String, Style)], name: &str) -> GridPlacement {
+        styles
+            .iter()
+            .find(|(style_name, _)| style_name == name)
+            .unwrap_or_else(|| panic!("no node named {name:?}"))
+            .1
+            .grid_column
+    }
+
+    #[test]
+    fn red_box_starts_at_inline_start_column_4() {
+        // The blue box has 4 columns.
+        let ltr = resolve_demo("en", test_ui::blue_box);
+        assert_eq!(physical_tracks(grid_column(&ltr, "Red box"), 4), (4, 4));
+        assert_eq!(physical_tracks(grid_column(&ltr, "Green box"), 4), (1, 3));
+
+        let rtl = resolve_demo("ar", test_ui::blue_box);
+        assert_eq!(physical_tracks(grid_column(&rtl, "Red box"), 4), (1, 1));
+        assert_eq!(physical_tracks(grid_column(&rtl, "Green box"), 4), (2, 4));
+    }
+
+    #[test]
+    fn mail_list_sender_comes_before_subject() {
+        // The mail list has 3 columns: sender, subject, then date.
+        let ltr = resolve_demo("en", mail_ui::mail_list);
+        assert_eq!(physical_tracks(grid_column(&ltr, "Gitpup"), 3), (1, 1));
+        assert_eq!(
+            physical_tracks(grid_column(&ltr, "Here is your OTP"), 3),
+            (2, 2)
+        );
+
+        let rtl = resolve_demo("ar", mail_ui::mail_list);
+        assert_eq!(physical_tracks(grid_column(&rtl, "Gitpup"), 3), (3, 3));
+        assert_eq!(
+            physical_tracks(grid_column(&rtl, "Here is your OTP"), 3),
+            (2, 2)
+        );
+    }
+
+    #[test]
+    fn red_box_is_laid_out_at_the_inline_start_of_column_4() {
+        let ltr = layout_demo("en", test_ui::blue_box);
+        assert!(center_x(&ltr, "Red box") > center_x(&ltr, "Green box"));
+
+        // The whole blue box mirrors, including its padding, so the red box mirrors exactly.
+        let rtl = layout_demo("ar", test_ui::blue_box);
+        assert!(center_x(&rtl, "Red box") < center_x(&rtl, "Green box"));
+        assert!(
+            (center_x(&rtl, "Red box") - (WINDOW_WIDTH - center_x(&ltr, "Red box"))).abs() < 1.
+        );
+        assert!(
+            (center_x(&rtl, "Green box") - (WINDOW_WIDTH - center_x(&ltr, "Green box"))).abs() < 1.
+        );
+    }
+
+    #[test]
+    fn mail_list_sender_is_laid_out_at_the_inline_start() {
+        let ltr = layout_demo("en", mail_ui::mail_list);
+        assert!(center_x(&ltr, "Gitpup") < center_x(&ltr, "Here is your OTP"));
+
+        let rtl = layout_demo("ar", mail_ui::mail_list);
+        assert!(center_x(&rtl, "Gitpup") > center_x(&rtl, "Here is your OTP"));
+    }
+
+    #[test]
+    fn end_relative_helpers_work_in_both_directions() {
+        let ltr =
+            |placement| physical_tracks(grid_placement_to_physical(placement, Direction::Ltr), 4);
+        let rtl =
+            |placement| physical_tracks(grid_placement_to_physical(placement, Direction::Rtl), 4);
+
+        assert_eq!(ltr(last_column()), (4, 4));
+        assert_eq!(rtl(last_column()), (1, 1));
+        assert_eq!(ltr(span_to_end(2)), (2, 4));
+        assert_eq!(rtl(span_to_end(2)), (1, 3));
+    }
+
+    #[test]
+    fn logical_rects_swap_their_inline_edges_in_rtl() {
+        let rect = LogicalRect {
+            inline_start: Val::Px(1.),
+            inline_end: Val::Px(2.),
+            block_start: Val::Px(3.),
+            block_end: Val::Px(4.),
+        };
+        assert_eq!(
+            rect.to_physical(Direction::Ltr),
+            UiRect::new(Val::Px(1.), Val::Px(2.), Val::Px(3.), Val::Px(4.))
+        );
+        assert_eq!(
+            rect.to_physical(Direction::Rtl),
+            UiRect::new(Val::Px(2.), Val::Px(1.), Val::Px(3.), Val::Px(4.))
+        );
+    }
+
+    #[test]
+    fn inline_insets_resolve_to_left_and_right() {
+        let inset = |start, end| LogicalStyle {
+            inset_inline_start: start,
+            inset_inline_end: end,
+            ..default()
+        };
+        let start = Some(Val::Px(6.));
+        let end = Some(Val::Percent(10.));
+
+        assert_eq!(inset(None, None).physical_inset(Direction::Ltr), None);
+        assert_eq!(inset(None, None).physical_inset(Direction::Rtl), None);
+        assert_eq!(
+            inset(start, None).physical_inset(Direction::Ltr),
+            Some((Val::Px(6.), Val::Auto))
+        );
+        assert_eq!(
+            inset(start, None).physical_inset(Direction::Rtl),
+            Some((Val::Auto, Val::Px(6.)))
+        );
+        assert_eq!(
+            inset(None, end).physical_inset(Direction::Ltr),
+            Some((Val::Auto, Val::Percent(10.)))
+        );
+        assert_eq!(
+            inset(None, end).physical_inset(Direction::Rtl),
+            Some((Val::Percent(10.), Val::Auto))
+        );
+        assert_eq!(
+            inset(start, end).physical_inset(Direction::Ltr),
+            Some((Val::Px(6.), Val::Percent(10.)))
+        );
+        assert_eq!(
+            inset(start, end).physical_inset(Direction::Rtl),
+            Some((Val::Percent(10.), Val::Px(6.)))
+        );
+    }
+
+    #[test]
+    fn border_radii_swap_their_inline_corners_in_rtl() {
+        let radius = LogicalBorderRadius {
+            start_start: Val::Px(1.),
+            start_end: Val::Px(2.),
+            end_start: Val::Px(3.),
+            end_end: Val::Px(4.),
+        };
+        assert_eq!(
+            radius.to_physical(Direction::Ltr),
+            BorderRadius {
+                top_left: Val::Px(1.),
+                top_right: Val::Px(2.),
+                bottom_left: Val::Px(3.),
+                bottom_right: Val::Px(4.),
+            }
+        );
+        assert_eq!(
+            radius.to_physical(Direction::Rtl),
+            BorderRadius {
+                top_left: Val::Px(2.),
+                top_right: Val::Px(1.),
+                bottom_left: Val::Px(4.),
+                bottom_right: Val::Px(3.),
+            }
+        );
+    }
+
+    #[test]
+    fn end_relative_items_follow_the_inline_end() {
+        let ltr = resolve_demo("en", test_ui::blue_box);
+        assert_eq!(physical_tracks(grid_column(&ltr, "Yellow box"), 4), (4, 4));
+        assert_eq!(physical_tracks(grid_column(&ltr, "Footer"), 4), (2, 4));
+
+        let rtl = resolve_demo("ar", test_ui::blue_box);
+        assert_eq!(physical_tracks(grid_column(&rtl, "Yellow box"), 4), (1, 1));
+        assert_eq!(physical_tracks(grid_column(&rtl, "Footer"), 4), (1, 3));
+    }
+
+    #[test]
+    fn mirroring_twice_restores_the_placement() {
+        for placement in [
+            GridPlacement::start(3),
+            GridPlacement::end(2),
+            GridPlacement::start_span(1, 3),
+            GridPlacement::end_span(-1, 2),
+            GridPlacement::start_end(2, -1),
+        ] {
+            let mirrored = grid_placement_to_physical(placement, Direction::Rtl);
+            assert_eq!(
+                physical_tracks(grid_placement_to_physical(mirrored, Direction::Rtl), 5),
+                physical_tracks(placement, 5),
+            );
+        }
+    }
+}
@@ -1,5 +1,15 @@
-use crate::{LocalizedImageFlip, LocalizedText, SwitchLanguageButton, UiAssets};
+use crate::{
+    bidi::{BidiText, TextDirection},
+    datetime::{DateTimeStyle, LocalizedDateTime},
+    direction::LayoutDirection,
+    greeting::Greeting,
+    language_picker::language_picker,
+    logical::LogicalStyle,
+    relative_time::RelativeTime,
+    ImageMirroring, LocalizedArgs, LocalizedDigits, LocalizedImage, LocalizedText, UiAssets,
+};
 use bevy::prelude::*;
+use chrono::{Local, TimeDelta};
 
 pub fn container(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     builder
@@ -71,35 +81,29 @@ pub fn logo(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
             ..default()
         })
         .with_children(|builder| {
-            builder.spawn(ImageBundle {
-                style: Style {
-                    width: Val::Px(50.),
-                    height: Val::Px(50.),
+            builder.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(50.),
+                        height: Val::Px(50.),
+                        ..default()
+                    },
+                    image: UiImage::new(ui_assets.images.logo.clone()),
                     ..default()
                 },
-                image: UiImage::new(ui_assets.images.logo.clone()),
-                ..default()
-            });
+                // The logo has no lettering, so every locale shows the default image.
+                LocalizedImage::new(ui_assets.images.logo.clone()),
+                ImageMirroring::Never,
+            ));
             builder.spawn((
                 Text::new("bevymail_logo_text"),
                 ui_assets.typographies.logo_text.clone(),
-                LocalizedText("bevymail_logo_text"),
+                LocalizedText::new("bevymail_logo_text"),
             ));
         });
 }
 pub fn language_switcher(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
-    builder
-        .spawn((
-            ButtonBundle {
-                background_color: BackgroundColor(Color::linear_rgb(0.8, 0.8, 0.8)),
-                border_color: BorderColor(Color::linear_rgb(0.6, 0.6, 0.6)),
-                ..default()
-            },
-            SwitchLanguageButton,
-        ))
-        .with_children(|builder| {
-            builder.spawn((Text::new("E/ع"), ui_assets.typographies.user_text.clone()));
-        });
+    language_picker(builder, ui_assets.typographies.user_text.clone());
 }
 pub fn user(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     builder
@@ -124,19 +128,14 @@ pub fn user(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                 image: UiImage::new(ui_assets.images.avatars.bear.clone()),
                 ..default()
             });
-            builder
-                .spawn((Text::default(), ui_assets.typographies.user_text.clone()))
-                .with_children(|builder| {
-                    builder.spawn((
-                        TextSpan::new("good_morning"),
-                        ui_assets.typographies.user_text.clone(),
-                        LocalizedText("good_morning"),
-                    ));
-                    builder.spawn((
-                        TextSpan::new("$USER"),
-                        ui_assets.typographies.user_text.clone(),
-                    ));
-                });
+            builder.spawn((
+                Text::new("greeting_morning"),
+                ui_assets.typographies.user_text.clone(),
+                LocalizedText::new("greeting_morning"),
+                LocalizedArgs::default(),
+                Greeting,
+                BidiText,
+            ));
         });
 }
 
@@ -181,7 +180,7 @@ pub fn left_navigation(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
             builder.spawn((
                 Text::new("folders"),
                 ui_assets.typographies.folder_text.clone(),
-                LocalizedText("folders"),
+                LocalizedText::new("folders"),
                 BackgroundColor(Color::hsl(0., 1., 0.5)),
             ));
             builder
@@ -202,20 +201,29 @@ pub fn left_navigation(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                         &ui_assets,
                         "inbox",
                         ui_assets.images.icons.inbox.clone(),
+                        ImageMirroring::Never,
                     );
                     folder_item(
                         builder,
                         &ui_assets,
                         "sent",
                         ui_assets.images.icons.sent.clone(),
+                        // Has a right-to-left version of its own rather than a flipped bitmap.
+                        ImageMirroring::SwapAsset(ui_assets.images.icons.sent_rtl.clone()),
                     );
                     folder_item(
                         builder,
                         &ui_assets,
                         "deleted",
                         ui_assets.images.icons.deleted.clone(),
+                        ImageMirroring::Never,
                     );
                 });
+            builder.spawn((
+                Text::new("unread_messages"),
+                ui_assets.typographies.folder_text.clone(),
+                LocalizedText::new("unread_messages").with_count(3),
+            ));
         });
 }
 
@@ -224,6 +232,7 @@ pub fn folder_item(
     ui_assets: &UiAssets,
     label: &'static str,
     icon: Handle<Image>,
+    mirroring: ImageMirroring,
 ) {
     builder
         .spawn(NodeBundle {
@@ -247,12 +256,12 @@ pub fn folder_item(
                     image: UiImage::new(icon),
                     ..default()
                 },
-                LocalizedImageFlip,
+                mirroring,
             ));
             builder.spawn((
                 Text::new(label),
                 ui_assets.typographies.folder_text.clone(),
-                LocalizedText(label),
+                LocalizedText::new(label),
             ));
         });
 }
@@ -317,8 +326,12 @@ pub fn mail_list(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                             width: Val::Percent(100.),
                             display: Display::Grid,
                             grid_template_rows: [RepeatedGridTrack::flex(4, 1.)].to_vec(),
-                            grid_template_columns: [GridTrack::auto(), GridTrack::flex(1.)]
-                                .to_vec(),
+                            grid_template_columns: [
+                                GridTrack::auto(),
+                                GridTrack::flex(1.),
+                                GridTrack::auto(),
+                            ]
+                            .to_vec(),
                             align_items: AlignItems::Center,
                             align_content: AlignContent::FlexStart,
                             justify_items: JustifyItems::Start,
@@ -332,20 +345,59 @@ pub fn mail_list(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                     },
                 ))
                 .with_children(|builder| {
-                    for (row, sender) in ["Gitpup", "Amashop", "Legitauth", "Bevymail", "Myself"]
+                    for (row, sender) in ["Gitpup", "Amashop", "Legitauth", "Bevymail"]
                         .into_iter()
                         .enumerate()
                     {
                         builder.spawn((
                             Style {
-                                grid_column: GridPlacement::start(1),
                                 grid_row: GridPlacement::start(row as i16 + 1),
                                 ..default()
                             },
+                            LogicalStyle {
+                                grid_column: Some(GridPlacement::start(1)),
+                                ..default()
+                            },
                             Text::new(sender),
                             ui_assets.typographies.mail_subject_text.clone(),
                         ));
                     }
+                    // Mails the user sent point the other way in right-to-left layouts.
+                    builder
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Row,
+                                    align_items: AlignItems::Center,
+                                    column_gap: Val::Px(4.),
+                                    grid_row: GridPlacement::start(5),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            LogicalStyle {
+                                grid_column: Some(GridPlacement::start(1)),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                ImageBundle {
+                                    style: Style {
+                                        width: Val::Px(16.),
+                                        height: Val::Px(16.),
+                                        ..default()
+                                    },
+                                    image: UiImage::new(ui_assets.images.icons.sent.clone()),
+                                    ..default()
+                                },
+                                ImageMirroring::Mirror,
+                            ));
+                            builder.spawn((
+                                Text::new("Myself"),
+                                ui_assets.typographies.mail_subject_text.clone(),
+                            ));
+                        });
                     for (row, subject) in [
                         "Your PR #513 has been merged",
                         "Order receipt #5164134",
@@ -358,18 +410,78 @@ pub fn mail_list(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                     {
                         builder.spawn((
                             Style {
-                                grid_column: GridPlacement::start(2),
                                 grid_row: GridPlacement::start(row as i16 + 1),
                                 ..default()
                             },
+                            LogicalStyle {
+                                grid_column: Some(GridPlacement::start(2)),
+                                ..default()
+                            },
                             Text::new(subject),
+                            LocalizedDigits(subject.to_string()),
                             ui_assets.typographies.mail_subject_text.clone(),
                         ));
                     }
+                    let now = Local::now().naive_local();
+                    for (row, age) in [
+                        TimeDelta::minutes(12),
+                        TimeDelta::hours(3),
+                        TimeDelta::hours(26),
+                        TimeDelta::days(5),
+                        TimeDelta::days(400),
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        builder
+                            .spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::FlexStart,
+                                        grid_row: GridPlacement::start(row as i16 + 1),
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                LogicalStyle {
+                                    grid_column: Some(GridPlacement::start(3)),
+                                    ..default()
+                                },
+                            ))
+                            .with_children(|builder| {
+                                builder.spawn((
+                                    Text::new("relative_just_now"),
+                                    ui_assets.typographies.mail_subject_text.clone(),
+                                    LocalizedText::new("relative_just_now"),
+                                    RelativeTime(now - age),
+                                ));
+                                builder.spawn((
+                                    Text::default(),
+                                    LocalizedDateTime {
+                                        datetime: now - age,
+                                        style: timestamp_style(age),
+                                    },
+                                    ui_assets.typographies.mail_subject_text.clone(),
+                                ));
+                            });
+                    }
                 });
         });
 }
 
+/// How much of the date of a mail to show in the mail list: only the time for today's mails,
+/// and the year for mails that are older than a year.
+fn timestamp_style(age: TimeDelta) -> DateTimeStyle {
+    if age < TimeDelta::days(1) {
+        DateTimeStyle::Time
+    } else if age < TimeDelta::days(365) {
+        DateTimeStyle::MonthDayTime
+    } else {
+        DateTimeStyle::ShortDate
+    }
+}
+
 pub fn mail_message(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     //     let message = "Welcome to Bevymail, $USER!
 
@@ -390,10 +502,11 @@ One Two Three, اربعة خمسة ستة، seven eight (nine), عشرة أحد
             NodeBundle {
                 style: Style {
                     height: Val::Percent(100.),
-                    flex_direction: FlexDirection::Row,
-                    align_items: AlignItems::FlexStart,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Stretch,
                     align_content: AlignContent::Stretch,
                     justify_content: JustifyContent::FlexStart,
+                    row_gap: Val::Px(16.),
                     grid_column: GridPlacement::start(1),
                     grid_row: GridPlacement::start(2),
                     padding: UiRect::all(Val::Px(5.)),
@@ -406,31 +519,114 @@ One Two Three, اربعة خمسة ستة، seven eight (nine), عشرة أحد
             },
         ))
         .with_children(|builder| {
+            builder.spawn((
+                Text::default(),
+                LocalizedDateTime {
+                    datetime: Local::now().naive_local(),
+                    style: DateTimeStyle::LongDate,
+                },
+                ui_assets.typographies.folder_text.clone(),
+            ));
+
+            // One text per block, so that each block is aligned to its own direction.
+            let mut part_index = 0;
+            for block in message.split("\n\n") {
+                builder
+                    .spawn((
+                        Text::default(),
+                        ui_assets.typographies.folder_text.clone(),
+                        BackgroundColor(Color::linear_rgb(0.1, 0.1, 0.1)),
+                        BidiText,
+                        TextDirection::Auto,
+                    ))
+                    .with_children(|builder| {
+                        for part in block.split_inclusive(|c: char| c.is_whitespace()) {
+                            let i = part_index;
+                            part_index += 1;
+                            builder.spawn((
+                                TextSpan::new(part.to_string()),
+                                TextStyle {
+                                    color: Color::hsl(i as f32 * 30.0, 0.8, 0.7),
+                                    font_size: match i % 3 {
+                                        0 => 16.,
+                                        1 => 12.,
+                                        2 => 20.,
+                                        _ => unreachable!(),
+                                    },
+                                    ..ui_assets.typographies.folder_text.clone()
+                                },
+                            ));
+                        }
+                    });
+            }
+
+            // Commands read left to right in every language.
             builder
                 .spawn((
-                    Text::default(),
-                    ui_assets.typographies.folder_text.clone(),
-                    BackgroundColor(Color::linear_rgb(0.1, 0.1, 0.1)),
+                    Name::new("Code block"),
+                    NodeBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(5.)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                        ..default()
+                    },
+                    LayoutDirection::Ltr,
                 ))
                 .with_children(|builder| {
-                    for (i, part) in message
-                        .split_inclusive(|c: char| c.is_whitespace())
-                        .enumerate()
-                    {
-                        builder.spawn((
-                            TextSpan::new(part.to_string()),
-                            TextStyle {
-                                color: Color::hsl(i as f32 * 30.0, 0.8, 0.7),
-                                font_size: match i % 3 {
-                                    0 => 16.,
-                                    1 => 12.,
-                                    2 => 20.,
-                                    _ => unreachable!(),
-                                },
-                                ..ui_assets.typographies.folder_text.clone()
-                            },
-                        ));
-                    }
+                    builder.spawn((
+                        Text::new("cargo run -- --lang ar"),
+                        ui_assets.typographies.folder_text.clone(),
+                        BidiText,
+                    ));
+                });
+
+            // An Arabic sentence that starts with a Latin name, which would make `Auto` pick
+            // left-to-right.
+            builder.spawn((
+                Text::new("Bevymail: أسرع بريد وأسهله."),
+                ui_assets.typographies.folder_text.clone(),
+                BidiText,
+                TextDirection::Rtl,
+            ));
+            // An English sentence, whose closing punctuation stays at its end in the Arabic UI.
+            builder.spawn((
+                Text::new("Sent from Bevymail (beta)."),
+                ui_assets.typographies.folder_text.clone(),
+                BidiText,
+                TextDirection::Ltr,
+            ));
+
+            // A quoted Arabic reply keeps its quote bar on its right, in the English UI too.
+            builder
+                .spawn((
+                    Name::new("Quoted reply"),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            column_gap: Val::Px(5.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    LayoutDirection::Rtl,
+                ))
+                .with_children(|builder| {
+                    builder.spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(3.),
+                            align_self: AlignSelf::Stretch,
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::hsl(0., 0., 0.6)),
+                        ..default()
+                    });
+                    builder.spawn((
+                        Text::new("شكراً على الرسالة!"),
+                        ui_assets.typographies.folder_text.clone(),
+                        BidiText,
+                    ));
                 });
         });
 }
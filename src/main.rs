@@ -1,37 +1,117 @@
+mod bidi;
+mod calendar;
+mod datetime;
+mod direction;
+mod greeting;
+mod language_identifier;
+mod language_picker;
+mod locale;
+mod localization;
+mod logical;
 mod mail_ui;
+mod plural;
+mod preferences;
+mod relative_time;
 mod test_ui;
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    ui::UiSystem,
+    utils::{HashMap, HashSet},
+};
+use calendar::CalendarDisplay;
+use direction::ComputedLayoutDirection;
+use language_identifier::LanguageIdentifier;
+use locale::{DigitPreference, DigitSystem, LocaleInfo, LocaleRegistry};
+use localization::{ArgValue, Pattern, TranslationsState};
 
 fn main() {
-    let localization_database = HashMap::from([
-        (("en", "bevymail_logo_text"), "Bevy mail!"),
-        (("ar", "bevymail_logo_text"), "بريد بَڤِي!"),
-        (("en", "good_morning"), "Good morning "),
-        (("ar", "good_morning"), "صباح الخير "),
-        (("en", "folders"), "Folders"),
-        (("ar", "folders"), "مجلدات"),
-        (("en", "inbox"), "Inbox"),
-        (("ar", "inbox"), "وارد"),
-        (("en", "sent"), "Sent"),
-        (("ar", "sent"), "صادر"),
-        (("en", "deleted"), "Deleted"),
-        (("ar", "deleted"), "محذوف"),
-    ]);
-
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins((DefaultPlugins, localization::LocalizationPlugin))
         .insert_resource(UiAssets::default())
-        .insert_resource(LocalizationDatabase(localization_database))
-        .insert_resource(CurrentLanguage("en"))
-        .add_systems(Startup, (setup, spawn_layout.after(setup)))
+        .insert_resource(LocaleRegistry::new(vec![
+            LocaleInfo {
+                id: "en".parse().unwrap(),
+                native_name: "English",
+                direction: None,
+                font: "fonts/NotoKufiArabic-Regular.ttf",
+                digits: DigitSystem::Latin,
+                calendar: CalendarDisplay::Gregorian,
+            },
+            LocaleInfo {
+                id: "ar".parse().unwrap(),
+                native_name: "العربية",
+                direction: None,
+                font: "fonts/NotoKufiArabic-Regular.ttf",
+                digits: DigitSystem::ArabicIndic,
+                calendar: CalendarDisplay::Both,
+            },
+            LocaleInfo {
+                id: "ar-SA".parse().unwrap(),
+                native_name: "العربية (السعودية)",
+                direction: None,
+                font: "fonts/NotoKufiArabic-Regular.ttf",
+                digits: DigitSystem::ArabicIndic,
+                calendar: CalendarDisplay::Hijri,
+            },
+        ]))
+        .insert_resource(CurrentLanguage("en".parse().unwrap()))
+        .insert_resource(FallbackLanguages(vec!["en".parse().unwrap()]))
+        .init_resource::<DigitPreference>()
+        .init_resource::<preferences::PreferencesFile>()
+        .init_resource::<relative_time::RelativeTimeTimer>()
+        .init_resource::<greeting::UserProfile>()
+        .init_resource::<greeting::GreetingTimer>()
+        .init_resource::<MissingTranslations>()
+        .add_systems(
+            Startup,
+            (
+                setup,
+                initial_language_system,
+                preferences::load_digit_preference_system,
+            ),
+        )
+        .add_systems(OnEnter(TranslationsState::Loaded), spawn_layout)
         .add_systems(
             Update,
             (
-                switch_language_button_system,
-                change_language_system.run_if(resource_changed::<CurrentLanguage>),
+                language_picker::populate_language_picker_system,
+                language_picker::language_picker_system,
+                language_picker::update_language_picker_label_system,
+                preferences::save_language_system,
+                relative_time::update_relative_times_system,
+                greeting::update_greetings_system,
+                change_language_system.run_if(
+                    resource_changed::<CurrentLanguage>
+                        .or(resource_changed::<LocalizationDatabase>)
+                        .or(resource_changed::<DigitPreference>),
+                ),
             ),
         )
+        // Runs after every `Update` system has spawned its entities, and before the UI is laid
+        // out, so new entities never show up with a raw key or the wrong direction.
+        .add_systems(
+            PostUpdate,
+            (
+                update_localized_text_system,
+                localize_new_entities_system,
+                shape_localized_digits_system,
+                datetime::localize_datetime_system,
+                direction::propagate_layout_direction_system,
+                localize_images_system,
+                mirror_images_system
+                    .after(localize_images_system)
+                    .after(direction::propagate_layout_direction_system),
+                logical::resolve_logical_style_system
+                    .after(direction::propagate_layout_direction_system),
+                logical::resolve_logical_border_radius_system
+                    .after(direction::propagate_layout_direction_system),
+                bidi::bidi_text_system
+                    .after(update_localized_text_system)
+                    .after(direction::propagate_layout_direction_system),
+            )
+                .before(UiSystem::Prepare),
+        )
         .run();
 }
 
@@ -68,6 +148,7 @@ struct Avatars {
 struct Icons {
     inbox: Handle<Image>,
     sent: Handle<Image>,
+    sent_rtl: Handle<Image>,
     deleted: Handle<Image>,
 }
 
@@ -108,91 +189,490 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut ui_assets:
     ui_assets.images.avatars.bear = asset_server.load("images/avatars/bear.png");
     ui_assets.images.icons.inbox = asset_server.load("images/icons/inbox.png");
     ui_assets.images.icons.sent = asset_server.load("images/icons/sent.png");
+    ui_assets.images.icons.sent_rtl = asset_server.load("images/icons/sent_rtl.png");
     ui_assets.images.icons.deleted = asset_server.load("images/icons/deleted.png");
 }
 
+/// Translated messages, keyed by language and then by message key.
+///
+/// Filled from the translation files in `assets/locales`, see [`localization`].
+#[derive(Resource, Default)]
+struct LocalizationDatabase(HashMap<LanguageIdentifier, HashMap<String, Pattern>>);
+
+impl LocalizationDatabase {
+    fn has_language(&self, language: &LanguageIdentifier) -> bool {
+        self.0.contains_key(language)
+    }
+
+    fn format(
+        &self,
+        language: &LanguageIdentifier,
+        digits: DigitSystem,
+        key: &str,
+        args: &HashMap<&str, ArgValue>,
+    ) -> Option<String> {
+        let pattern = self.0.get(language)?.get(key)?;
+        Some(pattern.format(language.language(), digits, args))
+    }
+}
+
 #[derive(Resource)]
-struct LocalizationDatabase(HashMap<(&'static str, &'static str), &'static str>);
+struct CurrentLanguage(LanguageIdentifier);
+
+/// The message keys that were reported missing, per language, so each is only warned about once.
+#[derive(Resource, Default)]
+struct MissingTranslations(HashSet<(LanguageIdentifier, &'static str)>);
+
+impl MissingTranslations {
+    /// Records that `key` is missing in `language`, and returns whether it is the first time.
+    fn report(&mut self, language: &LanguageIdentifier, key: &'static str) -> bool {
+        self.0.insert((language.clone(), key))
+    }
+}
 
+/// Languages whose messages are used, in order, when a key is missing in the current language.
+///
+/// More specific languages fall back to their parents first, so with `["en"]` a message for
+/// "ar-EG" is looked up in "ar-EG", then "ar", then "en".
 #[derive(Resource)]
-struct CurrentLanguage(&'static str);
+struct FallbackLanguages(Vec<LanguageIdentifier>);
+
+impl FallbackLanguages {
+    fn chain(&self, language: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+        let mut chain = vec![language.clone()];
+        while let Some(parent) = chain.last().unwrap().parent() {
+            chain.push(parent);
+        }
+        for fallback in &self.0 {
+            if !chain.contains(fallback) {
+                chain.push(fallback.clone());
+            }
+        }
+        chain
+    }
+}
 
 #[derive(Component)]
-struct LocalizedText(&'static str);
+struct LocalizedText {
+    key: &'static str,
+    /// Picks the plural variant of the message, which can refer to it as `$count`.
+    count: Option<i64>,
+}
+
+impl LocalizedText {
+    fn new(key: &'static str) -> Self {
+        Self { key, count: None }
+    }
+
+    fn with_count(mut self, count: i64) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
 
+/// Text whose ASCII digits are written with the digits of the current locale, for numbers that
+/// are not part of a translated message, like "Order receipt #5164134".
+///
+/// Holds the text with ASCII digits, which replaces the entity's `Text`.
 #[derive(Component)]
-struct LocalizedImageFlip;
+struct LocalizedDigits(String);
+
+/// Values for the `{ $name }` placeables in the message of the entity's [`LocalizedText`].
+///
+/// Changing an argument re-renders the text.
+#[derive(Component, Default)]
+struct LocalizedArgs(HashMap<&'static str, ArgValue>);
+
+impl LocalizedArgs {
+    fn with(mut self, name: &'static str, value: impl Into<ArgValue>) -> Self {
+        self.0.insert(name, value.into());
+        self
+    }
+}
 
+/// Shows a different image asset per locale, e.g. artwork with embedded lettering.
+///
+/// The image of a locale is loaded the first time that locale is shown. Locales without an
+/// image of their own, or their fallbacks, show the default image. Use
+/// [`ImageMirroring::SwapAsset`] for right-to-left artwork instead; the two do not combine.
 #[derive(Component)]
-struct SwitchLanguageButton;
+struct LocalizedImage {
+    default: Handle<Image>,
+    paths: HashMap<LanguageIdentifier, &'static str>,
+    handles: HashMap<LanguageIdentifier, Handle<Image>>,
+}
+
+impl LocalizedImage {
+    fn new(default: Handle<Image>) -> Self {
+        Self {
+            default,
+            paths: HashMap::default(),
+            handles: HashMap::default(),
+        }
+    }
+
+    /// Uses the image at asset `path` for `language`.
+    ///
+    /// No locale ships artwork of its own yet, so only the tests use this.
+    #[cfg(test)]
+    fn with_locale(mut self, language: LanguageIdentifier, path: &'static str) -> Self {
+        self.paths.insert(language, path);
+        self
+    }
+
+    fn handle(
+        &mut self,
+        asset_server: &AssetServer,
+        fallback_languages: &FallbackLanguages,
+        language: &LanguageIdentifier,
+    ) -> Handle<Image> {
+        let Some((language, path)) = fallback_languages
+            .chain(language)
+            .into_iter()
+            .find_map(|language| self.paths.get(&language).map(|&path| (language, path)))
+        else {
+            return self.default.clone();
+        };
+        self.handles
+            .entry(language)
+            .or_insert_with(|| asset_server.load(path))
+            .clone()
+    }
+}
+
+/// How an image follows the layout direction.
+#[derive(Component, Clone, PartialEq, Debug)]
+enum ImageMirroring {
+    /// Flipped horizontally in right-to-left layouts, e.g. for arrows.
+    Mirror,
+    /// Always shown as is, e.g. for logos, checkmarks and media controls.
+    Never,
+    /// Replaced by the given image in right-to-left layouts.
+    SwapAsset(Handle<Image>),
+}
+
+/// The image shown in left-to-right layouts, kept while it is swapped out by
+/// [`ImageMirroring::SwapAsset`].
+#[derive(Component)]
+struct LtrImage(Handle<Image>);
 
 fn change_language_system(
+    asset_server: Res<AssetServer>,
     current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
     localization_database: Res<LocalizationDatabase>,
+    fallback_languages: Res<FallbackLanguages>,
+    digit_preference: Res<DigitPreference>,
+    mut missing_translations: ResMut<MissingTranslations>,
     mut text_query: Query<
-        (&LocalizedText, Option<&mut Text>, Option<&mut TextSpan>),
+        (
+            &LocalizedText,
+            Option<&LocalizedArgs>,
+            Option<&mut Text>,
+            Option<&mut TextSpan>,
+        ),
         (Or<(With<Text>, With<TextSpan>)>,),
     >,
-    mut image_query: Query<&mut UiImage, With<LocalizedImageFlip>>,
-    mut direction_query: Query<&mut Style>,
+    mut font_query: Query<&mut TextStyle, With<LocalizedText>>,
 ) {
-    let is_left_to_right = match current_language.0 {
-        "ar" => false,
-        "en" => true,
-        _ => panic!("Unknown language"),
-    };
+    let locale = locale_registry.get(&current_language.0);
+    if locale.is_none() {
+        warn!(
+            "\"{}\" is not in the locale registry, keeping the current font",
+            current_language.0
+        );
+    }
 
-    for (localized_text, text, text_span) in &mut text_query {
-        if let Some(new_text) = localization_database
-            .0
-            .get(&(current_language.0, localized_text.0))
-        {
-            text.map(|mut t| t.0 = new_text.to_string());
-            text_span.map(|mut t| t.0 = new_text.to_string());
+    if let Some(locale) = locale {
+        let font = asset_server.load(locale.font);
+        for mut text_style in &mut font_query {
+            if text_style.font != font {
+                text_style.font = font.clone();
+            }
         }
     }
 
-    for mut ui_image in &mut image_query {
-        ui_image.flip_x = !is_left_to_right;
+    let digits = digit_preference.digits(locale);
+    for (localized_text, localized_args, text, text_span) in &mut text_query {
+        localize_text(
+            &localization_database,
+            &fallback_languages,
+            &mut missing_translations,
+            &current_language.0,
+            digits,
+            localized_text,
+            localized_args,
+            text,
+            text_span,
+        );
     }
+}
 
-    for mut style in &mut direction_query {
-        style.direction = if is_left_to_right {
-            Direction::Ltr
-        } else {
-            Direction::Rtl
-        };
+/// Renders texts that were spawned, or whose key, count or arguments changed, since the last
+/// language change.
+fn update_localized_text_system(
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    localization_database: Res<LocalizationDatabase>,
+    fallback_languages: Res<FallbackLanguages>,
+    digit_preference: Res<DigitPreference>,
+    mut missing_translations: ResMut<MissingTranslations>,
+    mut text_query: Query<
+        (
+            &LocalizedText,
+            Option<&LocalizedArgs>,
+            Option<&mut Text>,
+            Option<&mut TextSpan>,
+        ),
+        (
+            Or<(With<Text>, With<TextSpan>)>,
+            Or<(Changed<LocalizedText>, Changed<LocalizedArgs>)>,
+        ),
+    >,
+) {
+    let digits = digit_preference.digits(locale_registry.get(&current_language.0));
+    for (localized_text, localized_args, text, text_span) in &mut text_query {
+        localize_text(
+            &localization_database,
+            &fallback_languages,
+            &mut missing_translations,
+            &current_language.0,
+            digits,
+            localized_text,
+            localized_args,
+            text,
+            text_span,
+        );
     }
 }
 
-fn switch_language_button_system(
-    mut current_language: ResMut<CurrentLanguage>,
-    mut q: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (With<SwitchLanguageButton>, Changed<Interaction>),
+/// Gives texts spawned since the last language change the font of the current locale.
+fn localize_new_entities_system(
+    asset_server: Res<AssetServer>,
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    mut font_query: Query<&mut TextStyle, Added<LocalizedText>>,
+) {
+    if let Some(locale) = locale_registry.get(&current_language.0) {
+        let font = asset_server.load(locale.font);
+        for mut text_style in &mut font_query {
+            if text_style.font != font {
+                text_style.font = font.clone();
+            }
+        }
+    }
+}
+
+/// Writes the digits of every [`LocalizedDigits`] text with the digits of the current locale.
+fn shape_localized_digits_system(
+    current_language: Res<CurrentLanguage>,
+    locale_registry: Res<LocaleRegistry>,
+    digit_preference: Res<DigitPreference>,
+    mut text_query: Query<(Ref<LocalizedDigits>, &mut Text)>,
+) {
+    let changed = current_language.is_changed() || digit_preference.is_changed();
+    let digits = digit_preference.digits(locale_registry.get(&current_language.0));
+    for (localized_digits, mut text) in &mut text_query {
+        if !(changed || localized_digits.is_changed()) {
+            continue;
+        }
+        let shaped = digits.shape(&localized_digits.0);
+        if text.0 != shaped {
+            text.0 = shaped;
+        }
+    }
+}
+
+/// Shows the image of the current language in every [`LocalizedImage`].
+fn localize_images_system(
+    asset_server: Res<AssetServer>,
+    current_language: Res<CurrentLanguage>,
+    fallback_languages: Res<FallbackLanguages>,
+    mut image_query: Query<(&mut LocalizedImage, &mut UiImage)>,
+) {
+    for (mut localized_image, mut ui_image) in &mut image_query {
+        if !(current_language.is_changed() || localized_image.is_added()) {
+            continue;
+        }
+        // Loading an image only fills the cache, so it does not count as a change.
+        let texture = localized_image.bypass_change_detection().handle(
+            &asset_server,
+            &fallback_languages,
+            &current_language.0,
+        );
+        if ui_image.texture != texture {
+            ui_image.texture = texture;
+        }
+    }
+}
+
+/// Mirrors or swaps images in right-to-left parts of the UI, according to their
+/// [`ImageMirroring`].
+fn mirror_images_system(
+    mut commands: Commands,
+    mut image_query: Query<
+        (
+            Entity,
+            &ImageMirroring,
+            &mut UiImage,
+            &ComputedLayoutDirection,
+            Option<&LtrImage>,
+        ),
+        Or<(Changed<ComputedLayoutDirection>, Changed<ImageMirroring>)>,
     >,
 ) {
-    for (interaction, mut background_color, mut border_color) in &mut q {
-        match interaction {
-            Interaction::Pressed => {
-                if current_language.0 == "en" {
-                    current_language.0 = "ar"
-                } else {
-                    current_language.0 = "en"
+    for (entity, mirroring, mut ui_image, direction, ltr_image) in &mut image_query {
+        let ltr_texture = match ltr_image {
+            Some(ltr_image) => ltr_image.0.clone(),
+            None => {
+                if matches!(mirroring, ImageMirroring::SwapAsset(_)) {
+                    commands
+                        .entity(entity)
+                        .insert(LtrImage(ui_image.texture.clone()));
                 }
+                ui_image.texture.clone()
             }
-            Interaction::Hovered => {
-                *background_color = BackgroundColor(Color::linear_rgb(0.6, 0.6, 0.6));
-                *border_color = BorderColor(Color::linear_rgb(0., 0., 0.));
+        };
+
+        let (texture, flip_x) = match mirroring {
+            ImageMirroring::Mirror => (ltr_texture, direction.is_rtl()),
+            ImageMirroring::Never => (ltr_texture, false),
+            ImageMirroring::SwapAsset(rtl_texture) if direction.is_rtl() => {
+                (rtl_texture.clone(), false)
             }
-            Interaction::None => {
-                *background_color = BackgroundColor(Color::linear_rgb(0.8, 0.8, 0.8));
-                *border_color = BorderColor(Color::linear_rgb(0., 0., 0.));
+            ImageMirroring::SwapAsset(_) => (ltr_texture, false),
+        };
+        if ui_image.texture != texture {
+            ui_image.texture = texture;
+        }
+        if ui_image.flip_x != flip_x {
+            ui_image.flip_x = flip_x;
+        }
+    }
+}
+
+/// Renders the message of `localized_text` in `language`, or in the first of its fallbacks
+/// that has it. A message that is missing everywhere shows its key.
+fn localize_text(
+    localization_database: &LocalizationDatabase,
+    fallback_languages: &FallbackLanguages,
+    missing_translations: &mut MissingTranslations,
+    language: &LanguageIdentifier,
+    digits: DigitSystem,
+    localized_text: &LocalizedText,
+    localized_args: Option<&LocalizedArgs>,
+    text: Option<Mut<Text>>,
+    text_span: Option<Mut<TextSpan>>,
+) {
+    let mut args = localized_args
+        .map(|localized_args| localized_args.0.clone())
+        .unwrap_or_default();
+    if let Some(count) = localized_text.count {
+        args.insert("count", ArgValue::Number(count));
+    }
+
+    let chain = fallback_languages.chain(language);
+    let Some((found_language, new_text)) = chain.iter().find_map(|candidate| {
+        localization_database
+            .format(candidate, digits, localized_text.key, &args)
+            .map(|new_text| (candidate, new_text))
+    }) else {
+        // Until some translations are loaded, the key is expected to be missing.
+        if !chain
+            .iter()
+            .any(|candidate| localization_database.has_language(candidate))
+        {
+            return;
+        }
+        if missing_translations.report(language, localized_text.key) {
+            warn!(
+                "Missing translation for \"{}\" in \"{language}\" and its fallbacks",
+                localized_text.key
+            );
+        }
+        let key = localized_text.key.to_string();
+        text.map(|mut t| t.0 = key.clone());
+        text_span.map(|mut t| t.0 = key);
+        return;
+    };
+
+    // Until the translations of the current language are loaded, falling back is expected.
+    if found_language != language
+        && localization_database.has_language(language)
+        && missing_translations.report(language, localized_text.key)
+    {
+        warn!(
+            "Missing translation for \"{}\" in \"{language}\", using \"{found_language}\"",
+            localized_text.key
+        );
+    }
+
+    text.map(|mut t| t.0 = new_text.clone());
+    text_span.map(|mut t| t.0 = new_text);
+}
+
+/// Picks the language the app starts in, see [`initial_language`].
+fn initial_language_system(
+    locale_registry: Res<LocaleRegistry>,
+    preferences_file: Res<preferences::PreferencesFile>,
+    mut current_language: ResMut<CurrentLanguage>,
+) {
+    let language = initial_language(
+        &locale_registry,
+        command_line_language(std::env::args().skip(1)),
+        || preferences::saved_language(&locale_registry, &preferences_file),
+        locale::os_language,
+    );
+    if let Some(language) = language {
+        current_language.0 = language;
+    }
+}
+
+/// Returns the language given with `--lang <code>`, or else the one the user picked last time,
+/// or else the best match for the OS locale, or else `None` to keep the default.
+fn initial_language(
+    locale_registry: &LocaleRegistry,
+    command_line: Option<String>,
+    saved_language: impl FnOnce() -> Option<LanguageIdentifier>,
+    os_language: impl FnOnce() -> Option<LanguageIdentifier>,
+) -> Option<LanguageIdentifier> {
+    let from_command_line = command_line.and_then(|language| {
+        let locale = match LanguageIdentifier::parse(&language) {
+            Ok(id) => locale_registry.negotiate(&id),
+            Err(error) => {
+                warn!("Ignoring --lang {language}: {error}");
+                return None;
             }
+        };
+        if locale.is_none() {
+            warn!("Ignoring --lang {language}, it does not match any supported locale");
+        }
+        locale
+    });
+    from_command_line
+        .map(|locale| locale.id.clone())
+        .or_else(saved_language)
+        .or_else(|| {
+            os_language()
+                .and_then(|language| locale_registry.negotiate(&language))
+                .map(|locale| locale.id.clone())
+        })
+}
+
+/// Returns the value of the `--lang <code>` or `--lang=<code>` flag in the command-line `args`.
+fn command_line_language(mut args: impl Iterator<Item = String>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == "--lang" {
+            return args.next();
+        }
+        if let Some(language) = arg.strip_prefix("--lang=") {
+            return Some(language.to_string());
         }
     }
+    None
 }
 
+/// Spawns the mail client, or with `--layout-demo` the demo of logical layout properties.
 fn spawn_layout(mut commands: Commands, ui_assets: Res<UiAssets>) {
     commands
         .spawn(NodeBundle {
@@ -204,7 +684,352 @@ fn spawn_layout(mut commands: Commands, ui_assets: Res<UiAssets>) {
             ..default()
         })
         .with_children(|builder| {
-            //mail_ui::container(builder, &ui_assets);
-            test_ui::container(builder, &ui_assets);
+            if std::env::args().any(|arg| arg == "--layout-demo") {
+                test_ui::container(builder, &ui_assets);
+            } else {
+                mail_ui::container(builder, &ui_assets);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    const DEFAULT_LOGO: &str = "images/logo.png";
+    const ARABIC_LOGO: &str = "images/logo_ar.png";
+
+    fn texture_path(app: &App, entity: Entity) -> String {
+        let texture = &app.world().get::<UiImage>(entity).unwrap().texture;
+        texture.path().unwrap().to_string()
+    }
+
+    fn loaded_locales(app: &App, entity: Entity) -> usize {
+        app.world()
+            .get::<LocalizedImage>(entity)
+            .unwrap()
+            .handles
+            .len()
+    }
+
+    fn set_language(app: &mut App, language: &str) {
+        app.world_mut().resource_mut::<CurrentLanguage>().0 = language.parse().unwrap();
+        app.update();
+    }
+
+    #[test]
+    fn localized_images_follow_the_language() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .insert_resource(CurrentLanguage("en".parse().unwrap()))
+            .insert_resource(FallbackLanguages(vec!["en".parse().unwrap()]))
+            .add_systems(Update, localize_images_system);
+        let default_logo = app.world().resource::<AssetServer>().load(DEFAULT_LOGO);
+        let entity = app
+            .world_mut()
+            .spawn((
+                UiImage::new(default_logo.clone()),
+                LocalizedImage::new(default_logo).with_locale("ar".parse().unwrap(), ARABIC_LOGO),
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(texture_path(&app, entity), DEFAULT_LOGO);
+        // The Arabic image is not loaded until Arabic is shown.
+        assert_eq!(loaded_locales(&app, entity), 0);
+
+        set_language(&mut app, "ar");
+        assert_eq!(texture_path(&app, entity), ARABIC_LOGO);
+        assert_eq!(loaded_locales(&app, entity), 1);
+        let arabic_logo = app.world().get::<UiImage>(entity).unwrap().texture.id();
+
+        set_language(&mut app, "en");
+        assert_eq!(texture_path(&app, entity), DEFAULT_LOGO);
+
+        // Regional variants fall back to the image of their language, which is reused.
+        set_language(&mut app, "ar-EG");
+        assert_eq!(
+            app.world().get::<UiImage>(entity).unwrap().texture.id(),
+            arabic_logo
+        );
+        assert_eq!(loaded_locales(&app, entity), 1);
+
+        // Languages without an image of their own show the default one.
+        set_language(&mut app, "fa");
+        assert_eq!(texture_path(&app, entity), DEFAULT_LOGO);
+    }
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn reads_the_language_flag() {
+        assert_eq!(
+            command_line_language(args(&["--lang", "ar"])),
+            Some("ar".to_string())
+        );
+        assert_eq!(
+            command_line_language(args(&["--verbose", "--lang=ar-EG"])),
+            Some("ar-EG".to_string())
+        );
+        assert_eq!(command_line_language(args(&["--lang"])), None);
+        assert_eq!(command_line_language(args(&["ar"])), None);
+    }
+
+    #[test]
+    fn initial_language_priority() {
+        let registry = locale::tests::locale_registry();
+        let id = |id: &str| -> LanguageIdentifier { id.parse().unwrap() };
+        let initial = |command_line: Option<&str>, saved: Option<&str>, os: Option<&str>| {
+            initial_language(
+                &registry,
+                command_line.map(str::to_string),
+                || saved.map(id),
+                || os.map(id),
+            )
+        };
+
+        assert_eq!(initial(Some("ar"), Some("en"), Some("en")), Some(id("ar")));
+        assert_eq!(initial(None, Some("ar"), Some("en")), Some(id("ar")));
+        assert_eq!(initial(None, None, Some("ar-EG")), Some(id("ar")));
+        assert_eq!(initial(None, None, None), None);
+        // Flags and OS locales that do not match a supported locale are skipped.
+        assert_eq!(initial(Some("fr"), Some("ar"), None), Some(id("ar")));
+        assert_eq!(initial(Some("not a tag"), None, Some("ar")), Some(id("ar")));
+        assert_eq!(initial(None, None, Some("fr-FR")), None);
+        // The saved language is not read when a flag picks the language.
+        initial_language(
+            &registry,
+            Some("en".to_string()),
+            || panic!("read the saved language"),
+            || panic!("read the OS language"),
+        );
+    }
+
+    #[test]
+    fn fallback_chains() {
+        let fallback_languages = FallbackLanguages(vec!["en".parse().unwrap()]);
+        let chain = |language: &str| {
+            fallback_languages
+                .chain(&language.parse().unwrap())
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(chain("ar-EG"), ["ar-EG", "ar", "en"]);
+        assert_eq!(chain("pa-Arab-PK"), ["pa-Arab-PK", "pa-Arab", "pa", "en"]);
+        assert_eq!(chain("en-GB"), ["en-GB", "en"]);
+        assert_eq!(chain("en"), ["en"]);
+    }
+
+    /// An Arabic UI whose Arabic translations lack a message that the English ones have.
+    fn partially_translated_app() -> App {
+        let mut app = App::new();
+        let database = [
+            ("ar", "inbox = البريد الوارد"),
+            ("en", "inbox = Inbox\nsent = Sent"),
+        ]
+        .map(|(language, source)| {
+            (
+                language.parse().unwrap(),
+                localization::parse_messages(source).unwrap(),
+            )
         });
+        app.insert_resource(LocalizationDatabase(database.into_iter().collect()))
+            .insert_resource(locale::tests::locale_registry())
+            .insert_resource(CurrentLanguage("ar".parse().unwrap()))
+            .insert_resource(FallbackLanguages(vec!["en".parse().unwrap()]))
+            .init_resource::<DigitPreference>()
+            .init_resource::<MissingTranslations>()
+            .add_systems(Update, update_localized_text_system);
+        app
+    }
+
+    fn spawn_text(app: &mut App, key: &'static str) -> Entity {
+        app.world_mut()
+            .spawn((Text::new(key), LocalizedText::new(key)))
+            .id()
+    }
+
+    fn text(app: &App, entity: Entity) -> &str {
+        &app.world().get::<Text>(entity).unwrap().0
+    }
+
+    #[test]
+    fn missing_messages_fall_back() {
+        let mut app = partially_translated_app();
+        let inbox = spawn_text(&mut app, "inbox");
+        let sent = spawn_text(&mut app, "sent");
+        app.update();
+        assert_eq!(text(&app, inbox), "البريد الوارد");
+        assert_eq!(text(&app, sent), "Sent");
+    }
+
+    #[test]
+    fn messages_missing_everywhere_show_their_key_and_warn_once() {
+        let mut app = partially_translated_app();
+        let entity = app
+            .world_mut()
+            .spawn((Text::new("…"), LocalizedText::new("drafts")))
+            .id();
+        app.update();
+        assert_eq!(text(&app, entity), "drafts");
+        let missing = |app: &App| app.world().resource::<MissingTranslations>().0.clone();
+        assert_eq!(
+            missing(&app),
+            HashSet::from_iter([("ar".parse().unwrap(), "drafts")])
+        );
+
+        // Rendering it again, or another text with the same key, does not warn again.
+        app.world_mut()
+            .get_mut::<LocalizedText>(entity)
+            .unwrap()
+            .set_changed();
+        spawn_text(&mut app, "drafts");
+        app.update();
+        assert!(!app
+            .world_mut()
+            .resource_mut::<MissingTranslations>()
+            .report(&"ar".parse().unwrap(), "drafts"));
+        assert_eq!(missing(&app).len(), 1);
+    }
+
+    #[test]
+    fn entities_spawned_once_loaded_are_localized_on_their_first_frame() {
+        const ARABIC_FONT: &str = "fonts/NotoKufiArabic-Regular.ttf";
+        let mut app = partially_translated_app();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .init_asset::<Font>()
+            .init_state::<TranslationsState>()
+            .insert_resource(LocaleRegistry::new(vec![LocaleInfo {
+                id: "ar".parse().unwrap(),
+                native_name: "العربية",
+                direction: None,
+                font: ARABIC_FONT,
+                digits: DigitSystem::ArabicIndic,
+                calendar: CalendarDisplay::Both,
+            }]))
+            .add_systems(
+                OnEnter(TranslationsState::Loaded),
+                |mut commands: Commands| {
+                    commands.spawn((
+                        Text::new("inbox"),
+                        TextStyle::default(),
+                        LocalizedText::new("inbox"),
+                    ));
+                },
+            )
+            .add_systems(PostUpdate, localize_new_entities_system);
+        app.world_mut()
+            .resource_mut::<NextState<TranslationsState>>()
+            .set(TranslationsState::Loaded);
+
+        app.update();
+        let (text, text_style) = app
+            .world_mut()
+            .query_filtered::<(&Text, &TextStyle), With<LocalizedText>>()
+            .single(app.world());
+        assert_eq!(text.0, "البريد الوارد");
+        assert_eq!(text_style.font.path().unwrap().to_string(), ARABIC_FONT);
+    }
+
+    #[test]
+    fn images_follow_their_mirroring_policy() {
+        let ltr_texture = Handle::weak_from_u128(1);
+        let rtl_texture = Handle::weak_from_u128(2);
+        let mut app = App::new();
+        app.insert_resource(locale::tests::locale_registry())
+            .insert_resource(CurrentLanguage("ar".parse().unwrap()))
+            .add_systems(
+                Update,
+                (
+                    direction::propagate_layout_direction_system,
+                    mirror_images_system,
+                )
+                    .chain(),
+            );
+        let mut spawn_image = |mirroring| {
+            app.world_mut()
+                .spawn((
+                    ImageBundle {
+                        image: UiImage::new(ltr_texture.clone()),
+                        ..default()
+                    },
+                    mirroring,
+                ))
+                .id()
+        };
+        let mirrored = spawn_image(ImageMirroring::Mirror);
+        let swapped = spawn_image(ImageMirroring::SwapAsset(rtl_texture.clone()));
+        let never = spawn_image(ImageMirroring::Never);
+        app.world_mut()
+            .spawn(NodeBundle::default())
+            .add_children(&[mirrored, swapped, never]);
+
+        let image = |app: &App, entity| {
+            let image = app.world().get::<UiImage>(entity).unwrap();
+            (image.texture.clone(), image.flip_x)
+        };
+        for language in ["ar", "en", "ar"] {
+            app.world_mut().resource_mut::<CurrentLanguage>().0 = language.parse().unwrap();
+            app.update();
+            let rtl = language == "ar";
+            assert_eq!(
+                image(&app, mirrored),
+                (ltr_texture.clone(), rtl),
+                "{language}"
+            );
+            let swapped_texture = if rtl { &rtl_texture } else { &ltr_texture };
+            assert_eq!(
+                image(&app, swapped),
+                (swapped_texture.clone(), false),
+                "{language}"
+            );
+            assert_eq!(
+                image(&app, never),
+                (ltr_texture.clone(), false),
+                "{language}"
+            );
+        }
+    }
+
+    #[test]
+    fn digits_follow_the_language_and_preference() {
+        let mut app = App::new();
+        app.insert_resource(locale::tests::locale_registry())
+            .insert_resource(CurrentLanguage("en".parse().unwrap()))
+            .init_resource::<DigitPreference>()
+            .add_systems(Update, shape_localized_digits_system);
+        let entity = app
+            .world_mut()
+            .spawn((
+                Text::default(),
+                LocalizedDigits("Order receipt #5164134".to_string()),
+            ))
+            .id();
+        let text = |app: &App| app.world().get::<Text>(entity).unwrap().0.clone();
+
+        app.update();
+        assert_eq!(text(&app), "Order receipt #5164134");
+
+        app.world_mut().resource_mut::<CurrentLanguage>().0 = "ar".parse().unwrap();
+        app.update();
+        assert_eq!(text(&app), "Order receipt #٥١٦٤١٣٤");
+
+        *app.world_mut().resource_mut::<DigitPreference>() =
+            DigitPreference::Always(DigitSystem::ExtendedArabicIndic);
+        app.update();
+        assert_eq!(text(&app), "Order receipt #۵۱۶۴۱۳۴");
+
+        *app.world_mut().resource_mut::<DigitPreference>() = DigitPreference::Locale;
+        app.world_mut().resource_mut::<CurrentLanguage>().0 = "en".parse().unwrap();
+        app.update();
+        assert_eq!(text(&app), "Order receipt #5164134");
+    }
 }
@@ -0,0 +1,115 @@
+//! CLDR cardinal plural rules.
+//!
+//! See <https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html>.
+//! Only integer counts are supported, so the rules below are the CLDR rules with the
+//! visible fraction digits (`v`) fixed to zero.
+
+/// A CLDR plural category, used as a variant key in translation files, e.g. `[few]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zero" => Some(Self::Zero),
+            "one" => Some(Self::One),
+            "two" => Some(Self::Two),
+            "few" => Some(Self::Few),
+            "many" => Some(Self::Many),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the plural category of `count` in `language`.
+///
+/// Languages without rules here only have the `other` category, like the CLDR root locale.
+pub fn plural_category(language: &str, count: i64) -> PluralCategory {
+    let n = count.unsigned_abs();
+    let primary_language = language.split(['-', '_']).next().unwrap_or(language);
+
+    match primary_language {
+        "ar" => match (n, n % 100) {
+            (0, _) => PluralCategory::Zero,
+            (1, _) => PluralCategory::One,
+            (2, _) => PluralCategory::Two,
+            (_, 3..=10) => PluralCategory::Few,
+            (_, 11..=99) => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        },
+        "en" | "ur" => match n {
+            1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        },
+        "fa" => match n {
+            0 | 1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        },
+        "he" => match n {
+            1 => PluralCategory::One,
+            2 => PluralCategory::Two,
+            _ => PluralCategory::Other,
+        },
+        _ => PluralCategory::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(language: &str, counts: &[i64]) -> Vec<PluralCategory> {
+        counts
+            .iter()
+            .map(|&count| plural_category(language, count))
+            .collect()
+    }
+
+    #[test]
+    fn arabic() {
+        use PluralCategory::*;
+        assert_eq!(
+            categories(
+                "ar",
+                &[0, 1, 2, 3, 10, 11, 99, 100, 101, 102, 103, 111, 1000]
+            ),
+            [Zero, One, Two, Few, Few, Many, Many, Other, Other, Other, Few, Many, Other]
+        );
+        assert_eq!(categories("ar-EG", &[3]), [Few]);
+    }
+
+    #[test]
+    fn english() {
+        use PluralCategory::*;
+        assert_eq!(
+            categories("en", &[0, 1, 2, -1, 21]),
+            [Other, One, Other, One, Other]
+        );
+    }
+
+    #[test]
+    fn persian_and_hebrew() {
+        use PluralCategory::*;
+        assert_eq!(categories("fa", &[0, 1, 2]), [One, One, Other]);
+        assert_eq!(categories("he", &[1, 2, 3, 20]), [One, Two, Other, Other]);
+    }
+
+    #[test]
+    fn unknown_languages_only_have_other() {
+        assert_eq!(plural_category("ja", 1), PluralCategory::Other);
+    }
+
+    #[test]
+    fn category_names() {
+        assert_eq!(PluralCategory::from_name("few"), Some(PluralCategory::Few));
+        assert_eq!(PluralCategory::from_name("several"), None);
+    }
+}
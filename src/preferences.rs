@@ -0,0 +1,234 @@
+//! User preferences kept between runs, in `$XDG_CONFIG_HOME/bidi_demo/preferences`, or
+//! `~/.config/bidi_demo/preferences` when `XDG_CONFIG_HOME` is not set.
+//!
+//! The file has one `key = value` pair per line.
+
+use crate::{
+    language_identifier::LanguageIdentifier,
+    locale::{DigitPreference, DigitSystem},
+    CurrentLanguage, LocaleRegistry,
+};
+use bevy::{prelude::*, utils::HashMap};
+use std::{env, fs, io, path::PathBuf};
+
+const LANGUAGE_KEY: &str = "language";
+/// Overrides the digits of every locale with a CLDR numbering system, e.g. `digits = latn`.
+const DIGITS_KEY: &str = "digits";
+
+fn preferences_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("bidi_demo").join("preferences"))
+}
+
+/// The path of the preferences file, or `None` if there is no config directory.
+///
+/// Defaults to the file in the user's config directory.
+#[derive(Resource, Clone, Debug)]
+pub struct PreferencesFile(pub Option<PathBuf>);
+
+impl Default for PreferencesFile {
+    fn default() -> Self {
+        Self(preferences_path())
+    }
+}
+
+impl PreferencesFile {
+    fn read(&self) -> HashMap<String, String> {
+        let Some(path) = &self.0 else {
+            return HashMap::default();
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(
+                        "Could not read preferences from {}: {error}",
+                        path.display()
+                    );
+                }
+                return HashMap::default();
+            }
+        };
+        contents
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+
+    /// Sets `key` to `value` in the preferences file, keeping the other preferences.
+    fn write(&self, key: &str, value: &str) -> io::Result<()> {
+        let path = self.0.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no config directory, HOME is not set",
+            )
+        })?;
+        let mut preferences: Vec<(String, String)> = self.read().into_iter().collect();
+        preferences.retain(|(existing_key, _)| existing_key != key);
+        preferences.push((key.to_string(), value.to_string()));
+        preferences.sort();
+
+        let contents: String = preferences
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)
+    }
+}
+
+/// Returns the saved language, if it is one of the supported locales.
+pub fn saved_language(
+    locale_registry: &LocaleRegistry,
+    preferences_file: &PreferencesFile,
+) -> Option<LanguageIdentifier> {
+    let language = preferences_file.read().remove(LANGUAGE_KEY)?;
+    let locale = LanguageIdentifier::parse(&language)
+        .ok()
+        .and_then(|id| locale_registry.get(&id));
+    if locale.is_none() {
+        warn!("Ignoring saved language \"{language}\", it is not a supported locale");
+    }
+    locale.map(|locale| locale.id.clone())
+}
+
+/// Saves the language whenever the user picks another one.
+pub fn save_language_system(
+    current_language: Res<CurrentLanguage>,
+    preferences_file: Res<PreferencesFile>,
+) {
+    // The language the app started with is either saved already or a default.
+    if !current_language.is_changed() || current_language.is_added() {
+        return;
+    }
+    if let Err(error) = preferences_file.write(LANGUAGE_KEY, &current_language.0.to_string()) {
+        warn!("Could not save the language: {error}");
+    }
+}
+
+/// Applies the digits the user prefers over those of the locale, if any.
+pub fn load_digit_preference_system(
+    preferences_file: Res<PreferencesFile>,
+    mut digit_preference: ResMut<DigitPreference>,
+) {
+    let Some(name) = preferences_file.read().remove(DIGITS_KEY) else {
+        return;
+    };
+    match DigitSystem::from_cldr_name(&name) {
+        Some(digits) => *digit_preference = DigitPreference::Always(digits),
+        None => warn!("Ignoring unknown digits preference \"{name}\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::tests::locale_registry;
+
+    /// A preferences file in a directory of its own under the system temp directory, which is
+    /// removed when it is dropped.
+    struct TempPreferences(PreferencesFile);
+
+    impl TempPreferences {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("bidi_demo-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            Self(PreferencesFile(Some(dir.join("preferences"))))
+        }
+
+        fn path(&self) -> &PathBuf {
+            self.0 .0.as_ref().unwrap()
+        }
+    }
+
+    impl Drop for TempPreferences {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(self.path().parent().unwrap());
+        }
+    }
+
+    fn app(preferences: &TempPreferences) -> App {
+        let mut app = App::new();
+        app.insert_resource(locale_registry())
+            .insert_resource(preferences.0.clone())
+            .insert_resource(CurrentLanguage("en".parse().unwrap()))
+            .init_resource::<DigitPreference>()
+            .add_systems(Startup, load_digit_preference_system)
+            .add_systems(Update, save_language_system);
+        app
+    }
+
+    #[test]
+    fn preferences_round_trip() {
+        let preferences = TempPreferences::new("round_trip");
+        preferences.0.write(DIGITS_KEY, "arabext").unwrap();
+        let mut app = app(&preferences);
+        app.update();
+        assert_eq!(
+            *app.world().resource::<DigitPreference>(),
+            DigitPreference::Always(DigitSystem::ExtendedArabicIndic)
+        );
+
+        app.world_mut().resource_mut::<CurrentLanguage>().0 = "ar".parse().unwrap();
+        app.update();
+        assert_eq!(
+            saved_language(&locale_registry(), &preferences.0),
+            Some("ar".parse().unwrap())
+        );
+        // Saving the language keeps the other preferences.
+        assert_eq!(
+            fs::read_to_string(preferences.path()).unwrap(),
+            "digits = arabext\nlanguage = ar\n"
+        );
+    }
+
+    #[test]
+    fn the_initial_language_is_not_saved() {
+        let preferences = TempPreferences::new("initial_language");
+        let mut app = app(&preferences);
+        app.update();
+        app.update();
+        assert!(!preferences.path().exists());
+    }
+
+    #[test]
+    fn missing_preferences_keep_the_defaults() {
+        let preferences = TempPreferences::new("missing");
+        let mut app = app(&preferences);
+        app.update();
+        assert_eq!(
+            *app.world().resource::<DigitPreference>(),
+            DigitPreference::Locale
+        );
+        assert_eq!(saved_language(&locale_registry(), &preferences.0), None);
+        assert_eq!(
+            saved_language(&locale_registry(), &PreferencesFile(None)),
+            None
+        );
+    }
+
+    #[test]
+    fn corrupt_preferences_keep_the_defaults() {
+        let preferences = TempPreferences::new("corrupt");
+        fs::create_dir_all(preferences.path().parent().unwrap()).unwrap();
+        for contents in [
+            &b"language = tlh\ndigits = roman\nnot a preference\n"[..],
+            &[0xff, 0xfe, 0x00][..],
+        ] {
+            fs::write(preferences.path(), contents).unwrap();
+            let mut app = app(&preferences);
+            app.update();
+            assert_eq!(
+                *app.world().resource::<DigitPreference>(),
+                DigitPreference::Locale
+            );
+            assert_eq!(saved_language(&locale_registry(), &preferences.0), None);
+        }
+    }
+}
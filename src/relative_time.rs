@@ -0,0 +1,157 @@
+//! Relative times like "3 hours ago" or "yesterday".
+//!
+//! A [`RelativeTime`] only picks the message key and count of the entity's [`LocalizedText`],
+//! so the phrase is rendered like any other message, with the plural rules of the language.
+
+use crate::LocalizedText;
+use bevy::prelude::*;
+use chrono::{Local, NaiveDateTime};
+
+/// How often relative times are brought up to date.
+const REFRESH_INTERVAL_SECONDS: f32 = 30.;
+
+/// Shows how long ago a moment was in the entity's [`LocalizedText`].
+#[derive(Component)]
+pub struct RelativeTime(pub NaiveDateTime);
+
+#[derive(Resource)]
+pub struct RelativeTimeTimer(Timer);
+
+impl Default for RelativeTimeTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            REFRESH_INTERVAL_SECONDS,
+            TimerMode::Repeating,
+        ))
+    }
+}
+
+/// Returns the message key and count that describe `datetime` as seen at `now`.
+///
+/// Times less than a day ago are counted in hours even across midnight, and "yesterday" starts
+/// after that. Times in the future, from a clock that is ahead of the local one, are shown as
+/// "just now" rather than as a negative count.
+fn relative_time_message(
+    datetime: NaiveDateTime,
+    now: NaiveDateTime,
+) -> (&'static str, Option<i64>) {
+    let elapsed = now - datetime;
+    let days = (now.date() - datetime.date()).num_days();
+    if elapsed.num_minutes() < 1 {
+        ("relative_just_now", None)
+    } else if elapsed.num_hours() < 1 {
+        ("relative_minutes_ago", Some(elapsed.num_minutes()))
+    } else if elapsed.num_hours() < 24 {
+        ("relative_hours_ago", Some(elapsed.num_hours()))
+    } else if days == 1 {
+        ("relative_yesterday", None)
+    } else {
+        ("relative_days_ago", Some(days))
+    }
+}
+
+/// Updates relative times when they are spawned, and then periodically as time passes.
+pub fn update_relative_times_system(
+    time: Res<Time>,
+    mut timer: ResMut<RelativeTimeTimer>,
+    mut text_query: Query<(Ref<RelativeTime>, &mut LocalizedText)>,
+) {
+    let refresh = timer.0.tick(time.delta()).just_finished();
+    let now = Local::now().naive_local();
+    for (relative_time, mut localized_text) in &mut text_query {
+        if !(refresh || relative_time.is_changed()) {
+            continue;
+        }
+        let (key, count) = relative_time_message(relative_time.0, now);
+        if localized_text.key != key || localized_text.count != count {
+            localized_text.key = key;
+            localized_text.count = count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn message(ago: Duration, now: NaiveDateTime) -> (&'static str, Option<i64>) {
+        relative_time_message(now - ago, now)
+    }
+
+    #[test]
+    fn minutes_and_hours() {
+        let now = at(18, 21, 5);
+        assert_eq!(message(Duration::zero(), now), ("relative_just_now", None));
+        assert_eq!(
+            message(Duration::seconds(59), now),
+            ("relative_just_now", None)
+        );
+        assert_eq!(
+            message(Duration::minutes(1), now),
+            ("relative_minutes_ago", Some(1))
+        );
+        assert_eq!(
+            message(Duration::minutes(59), now),
+            ("relative_minutes_ago", Some(59))
+        );
+        assert_eq!(
+            message(Duration::hours(1), now),
+            ("relative_hours_ago", Some(1))
+        );
+        assert_eq!(
+            message(Duration::minutes(23 * 60 + 59), now),
+            ("relative_hours_ago", Some(23))
+        );
+    }
+
+    #[test]
+    fn hours_across_midnight() {
+        assert_eq!(
+            message(Duration::hours(2), at(18, 1, 0)),
+            ("relative_hours_ago", Some(2))
+        );
+    }
+
+    #[test]
+    fn days() {
+        let now = at(18, 21, 5);
+        assert_eq!(
+            message(Duration::minutes(24 * 60 + 30), now),
+            ("relative_yesterday", None)
+        );
+        assert_eq!(
+            message(Duration::days(2), now),
+            ("relative_days_ago", Some(2))
+        );
+        assert_eq!(
+            message(Duration::days(11), now),
+            ("relative_days_ago", Some(11))
+        );
+        // Counted in calendar days once more than a day has passed.
+        assert_eq!(
+            message(Duration::hours(30), at(18, 1, 0)),
+            ("relative_days_ago", Some(2))
+        );
+    }
+
+    #[test]
+    fn future_times_are_just_now() {
+        let now = at(18, 21, 5);
+        assert_eq!(
+            message(-Duration::seconds(30), now),
+            ("relative_just_now", None)
+        );
+        assert_eq!(
+            message(-Duration::days(3), now),
+            ("relative_just_now", None)
+        );
+    }
+}
@@ -1,30 +1,40 @@
-use crate::{LocalizedText, SwitchLanguageButton, UiAssets};
+use crate::{
+    language_picker::language_picker,
+    logical::{last_column, span_to_end, LogicalBorderRadius, LogicalRect, LogicalStyle},
+    LocalizedText, UiAssets,
+};
 use bevy::prelude::*;
 
 pub fn container(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     let background_color = BackgroundColor(Color::hsl(0., 0., 0.));
+    let padding = LogicalRect {
+        inline_start: Val::Px(10.),
+        inline_end: Val::Px(20.),
+        block_start: Val::Px(10.),
+        block_end: Val::Px(20.),
+    };
 
     builder
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                flex_wrap: FlexWrap::NoWrap,
-                align_items: AlignItems::FlexStart,
-                row_gap: Val::Px(50.),
-                padding: UiRect {
-                    left: Val::Px(10.),
-                    right: Val::Px(20.),
-                    top: Val::Px(10.),
-                    bottom: Val::Px(20.),
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    flex_wrap: FlexWrap::NoWrap,
+                    align_items: AlignItems::FlexStart,
+                    row_gap: Val::Px(50.),
+                    ..default()
                 },
+                background_color,
                 ..default()
             },
-            background_color,
-            ..default()
-        })
+            LogicalStyle {
+                padding: Some(padding),
+                ..default()
+            },
+        ))
         .with_children(|builder| {
             banner(builder, &ui_assets);
             blue_box(builder, &ui_assets);
@@ -54,80 +64,131 @@ pub fn banner(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
                 ui_assets.typographies.test_banner.clone(),
             ));
 
-            builder
-                .spawn((
-                    ButtonBundle {
-                        background_color: BackgroundColor(Color::linear_rgb(0.8, 0.8, 0.8)),
-                        border_color: BorderColor(Color::linear_rgb(0.6, 0.6, 0.6)),
-                        ..default()
-                    },
-                    SwitchLanguageButton,
-                ))
-                .with_children(|builder| {
-                    builder.spawn((Text::new("E/ع"), ui_assets.typographies.test_banner.clone()));
-                });
+            language_picker(builder, ui_assets.typographies.test_banner.clone());
         });
 }
 
 pub fn blue_box(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     let background_color = BackgroundColor(Color::hsl(240., 0.33, 0.30));
+    let padding = LogicalRect {
+        inline_start: Val::Px(10.),
+        inline_end: Val::Px(20.),
+        block_start: Val::Px(10.),
+        block_end: Val::Px(20.),
+    };
     builder
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                display: Display::Grid,
-                grid_template_rows: [RepeatedGridTrack::flex(5, 1.)].to_vec(),
-                grid_template_columns: [RepeatedGridTrack::flex(4, 1.)].to_vec(),
-                align_items: AlignItems::Start,
-                align_content: AlignContent::Stretch,
-                justify_items: JustifyItems::Start,
-                justify_content: JustifyContent::Stretch,
-                row_gap: Val::Px(5.),
-                column_gap: Val::Px(10.),
-                padding: UiRect {
-                    left: Val::Px(10.),
-                    right: Val::Px(20.),
-                    top: Val::Px(10.),
-                    bottom: Val::Px(20.),
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    display: Display::Grid,
+                    grid_template_rows: [RepeatedGridTrack::flex(6, 1.)].to_vec(),
+                    grid_template_columns: [RepeatedGridTrack::flex(4, 1.)].to_vec(),
+                    align_items: AlignItems::Start,
+                    align_content: AlignContent::Stretch,
+                    justify_items: JustifyItems::Start,
+                    justify_content: JustifyContent::Stretch,
+                    row_gap: Val::Px(5.),
+                    column_gap: Val::Px(10.),
+                    ..default()
                 },
+                background_color,
                 ..default()
             },
-            background_color,
-            ..default()
-        })
+            LogicalStyle {
+                padding: Some(padding),
+                ..default()
+            },
+        ))
         .with_children(|builder| {
             green_box(builder, &ui_assets);
             red_box(builder, &ui_assets);
+            yellow_box(builder, &ui_assets);
+            footer(builder, &ui_assets);
         });
 }
 
+/// Fills the top of the last column, whatever the number of columns.
+pub fn yellow_box(builder: &mut ChildBuilder, _ui_assets: &UiAssets) {
+    builder.spawn((
+        Name::new("Yellow box"),
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                grid_row: GridPlacement::start_span(1, 2),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::hsl(60., 0.33, 0.30)),
+            ..default()
+        },
+        LogicalStyle {
+            grid_column: Some(last_column()),
+            ..default()
+        },
+    ));
+}
+
+/// Spans from the second column to the inline end of the grid.
+pub fn footer(builder: &mut ChildBuilder, _ui_assets: &UiAssets) {
+    builder.spawn((
+        Name::new("Footer"),
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                grid_row: GridPlacement::start(6),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::hsl(180., 0.33, 0.30)),
+            ..default()
+        },
+        LogicalStyle {
+            grid_column: Some(span_to_end(2)),
+            ..default()
+        },
+    ));
+}
+
 pub fn green_box(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     let background_color = BackgroundColor(Color::hsl(120., 0.33, 0.30));
     let border_color = BorderColor(Color::hsl(120., 0.33, 0.60));
+    let border = LogicalRect {
+        inline_start: Val::Px(2.),
+        inline_end: Val::Px(5.),
+        block_start: Val::Px(2.),
+        block_end: Val::Px(5.),
+    };
     builder
-        .spawn(NodeBundle {
-            style: Style {
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                flex_wrap: FlexWrap::Wrap,
-                align_content: AlignContent::FlexStart,
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                grid_column: GridPlacement::start_span(1, 3),
-                grid_row: GridPlacement::start_span(1, 4),
-                border: UiRect {
-                    left: Val::Px(2.),
-                    right: Val::Px(5.),
-                    top: Val::Px(2.),
-                    bottom: Val::Px(5.),
+        .spawn((
+            Name::new("Green box"),
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    flex_wrap: FlexWrap::Wrap,
+                    align_content: AlignContent::FlexStart,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    grid_row: GridPlacement::start_span(1, 4),
+                    ..default()
                 },
+                background_color,
+                border_color,
                 ..default()
             },
-            background_color,
-            border_color,
-            ..default()
-        })
+            LogicalStyle {
+                border: Some(border),
+                grid_column: Some(GridPlacement::start_span(1, 3)),
+                ..default()
+            },
+            // Shaped like a chat bubble pointing at the inline start.
+            LogicalBorderRadius {
+                start_start: Val::ZERO,
+                ..LogicalBorderRadius::all(Val::Px(16.))
+            },
+        ))
         .with_children(|builder| {
             for i in 1..=10 {
                 pink_box(builder, &ui_assets, i);
@@ -149,23 +210,27 @@ pub fn pink_box(builder: &mut ChildBuilder, ui_assets: &UiAssets, index: usize)
     } else {
         index as f32 * 2.0
     };
+    let border = LogicalRect {
+        inline_start: Val::Px(2.),
+        inline_end: Val::Px(5.),
+        block_start: Val::Px(2.),
+        block_end: Val::Px(5.),
+    };
     builder.spawn((
         NodeBundle {
             style: Style {
                 width: Val::Px(100.0 + width_extra),
                 height: Val::Px(50.0 + height_extra),
-                border: UiRect {
-                    left: Val::Px(2.),
-                    right: Val::Px(5.),
-                    top: Val::Px(2.),
-                    bottom: Val::Px(5.),
-                },
                 ..default()
             },
             background_color,
             border_color,
             ..default()
         },
+        LogicalStyle {
+            border: Some(border),
+            ..default()
+        },
         //Text(format!("{}", index)),
     ));
 }
@@ -173,22 +238,56 @@ pub fn pink_box(builder: &mut ChildBuilder, ui_assets: &UiAssets, index: usize)
 pub fn red_box(builder: &mut ChildBuilder, ui_assets: &UiAssets) {
     let background_color = BackgroundColor(Color::hsl(0., 0.33, 0.30));
     let border_color = BorderColor(Color::hsl(0., 0.33, 0.60));
-    builder.spawn(NodeBundle {
-        style: Style {
-            width: Val::Percent(100.),
-            height: Val::Percent(100.),
-            grid_column: GridPlacement::start(4),
-            grid_row: GridPlacement::start_span(3, 3),
-            border: UiRect {
-                left: Val::Px(2.),
-                right: Val::Px(5.),
-                top: Val::Px(2.),
-                bottom: Val::Px(5.),
+    let border = LogicalRect {
+        inline_start: Val::Px(2.),
+        inline_end: Val::Px(5.),
+        block_start: Val::Px(2.),
+        block_end: Val::Px(5.),
+    };
+    builder
+        .spawn((
+            Name::new("Red box"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    grid_row: GridPlacement::start_span(3, 3),
+                    ..default()
+                },
+                background_color,
+                border_color,
+                ..default()
+            },
+            LogicalStyle {
+                border: Some(border),
+                grid_column: Some(GridPlacement::start(4)),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            badge(builder, &ui_assets);
+        });
+}
+
+/// A small marker pinned to the top inline-start corner of its container.
+pub fn badge(builder: &mut ChildBuilder, _ui_assets: &UiAssets) {
+    let background_color = BackgroundColor(Color::hsl(50., 0.8, 0.5));
+    builder.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(-6.),
+                width: Val::Px(12.),
+                height: Val::Px(12.),
+                ..default()
             },
+            background_color,
+            border_radius: BorderRadius::MAX,
             ..default()
         },
-        background_color,
-        border_color,
-        ..default()
-    });
+        LogicalStyle {
+            inset_inline_start: Some(Val::Px(-6.)),
+            ..default()
+        },
+    ));
 }